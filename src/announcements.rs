@@ -0,0 +1,61 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+use chrono::{TimeDelta, Utc};
+use poise::serenity_prelude::{Colour, CreateAllowedMentions, CreateMessage, Http};
+use crate::cache::LaunchCache;
+use crate::store::Store;
+
+const ANNOUNCE_INTERVAL: Duration = Duration::from_secs(300);
+const ANNOUNCE_LEAD: TimeDelta = TimeDelta::hours(1);
+
+pub fn spawn(http: Arc<Http>, launches: Arc<LaunchCache>, store: Arc<Store>, colour: Colour) {
+    tokio::spawn(async move {
+        let mut announced = HashSet::new();
+        let mut interval = tokio::time::interval(ANNOUNCE_INTERVAL);
+
+        loop {
+            interval.tick().await;
+
+            let flights = match launches.launches().await {
+                Ok(flights) => flights,
+                Err(why) => {
+                    eprintln!("Skipping announcements, couldn't load launches: {}", why);
+                    continue;
+                }
+            };
+
+            let now = Utc::now();
+            let targets: Vec<_> = store
+                .guilds()
+                .into_iter()
+                .filter_map(|(_, settings)| Some((settings.announcement_channel?, settings.announcement_role)))
+                .collect();
+
+            for flight in flights {
+                if flight.approximate || flight.time < now || flight.time - now > ANNOUNCE_LEAD {
+                    continue;
+                }
+                if !announced.insert(flight.key()) {
+                    continue;
+                }
+
+                for (channel, role) in &targets {
+                    let content = match role {
+                        Some(role) => format!("<@&{}> 🚀 Launching within the hour!", role),
+                        None => String::from("🚀 Launching within the hour!"),
+                    };
+                    let mentions = CreateAllowedMentions::new().roles(role.iter().copied());
+                    let message = CreateMessage::new()
+                        .content(content)
+                        .allowed_mentions(mentions)
+                        .embed(flight.base_embed(colour));
+
+                    if let Err(why) = channel.send_message(&http, message).await {
+                        eprintln!("Failed to announce {} in {}: {}", flight.name.trim(), channel, why);
+                    }
+                }
+            }
+        }
+    });
+}
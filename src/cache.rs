@@ -0,0 +1,194 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+use chrono::{DateTime, Utc};
+use poise::serenity_prelude::UserId;
+use crate::Error;
+use crate::error::SplinkError;
+use crate::details::LaunchDetails;
+use crate::launches::FlightData;
+use crate::sources::DataSource;
+
+const CACHE_TTL: Duration = Duration::from_secs(600);
+const DETAIL_TTL: Duration = Duration::from_secs(1800);
+const SUSPECT_DROP: f64 = 0.5;
+const SUSPECT_RETRY_DELAY: Duration = Duration::from_secs(5);
+const MAX_SUSPECT_REFRESHES: usize = 3;
+
+struct CachedLaunches {
+    launches: Vec<FlightData>,
+    source: &'static str,
+    fetched_at: Instant,
+    fetched_on: DateTime<Utc>,
+}
+
+pub struct Provenance {
+    pub source: &'static str,
+    pub fetched_on: DateTime<Utc>,
+    pub launch_count: usize,
+    pub fresh: bool,
+}
+
+pub struct LaunchCache {
+    source: Arc<dyn DataSource>,
+    entry: Mutex<Option<CachedLaunches>>,
+    previous: Mutex<Option<Vec<FlightData>>>,
+    pages: Mutex<HashMap<UserId, usize>>,
+    suspect_refreshes: AtomicUsize,
+}
+
+impl LaunchCache {
+    pub fn new(source: Arc<dyn DataSource>) -> LaunchCache {
+        LaunchCache {
+            source,
+            entry: Mutex::new(None),
+            previous: Mutex::new(None),
+            pages: Mutex::default(),
+            suspect_refreshes: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn is_fresh(&self) -> bool {
+        self.entry
+            .lock()
+            .unwrap()
+            .as_ref()
+            .is_some_and(|cached| cached.fetched_at.elapsed() < CACHE_TTL)
+    }
+
+    fn fresh(&self) -> Option<Vec<FlightData>> {
+        self.entry
+            .lock()
+            .unwrap()
+            .as_ref()
+            .filter(|cached| cached.fetched_at.elapsed() < CACHE_TTL)
+            .map(|cached| cached.launches.clone())
+    }
+
+    fn is_suspect(&self, count: usize) -> bool {
+        self.entry
+            .lock()
+            .unwrap()
+            .as_ref()
+            .is_some_and(|cached| (count as f64) < cached.launches.len() as f64 * SUSPECT_DROP)
+    }
+
+    fn is_suspect_result(&self, fetched: &Result<(Vec<FlightData>, &'static str), Error>) -> bool {
+        match fetched {
+            Ok((launches, _)) => self.is_suspect(launches.len()),
+            Err(SplinkError::LayoutChanged) => self.is_suspect(0),
+            Err(_) => false,
+        }
+    }
+
+    fn keep_cached(&self) -> Option<Vec<FlightData>> {
+        let mut entry = self.entry.lock().unwrap();
+        let cached = entry.as_mut()?;
+        cached.fetched_at = Instant::now();
+        Some(cached.launches.clone())
+    }
+
+    pub async fn refresh(&self) -> Result<Vec<FlightData>, Error> {
+        self.fetch_and_store(true).await
+    }
+
+    async fn fetch_and_store(&self, retry_suspect: bool) -> Result<Vec<FlightData>, Error> {
+        let mut fetched = self.source.fetch_tagged().await;
+        if retry_suspect && self.is_suspect_result(&fetched) {
+            eprintln!("Fetched a suspiciously small listing, refetching in case the page was incomplete");
+            tokio::time::sleep(SUSPECT_RETRY_DELAY).await;
+            match self.source.fetch_tagged().await {
+                Err(why) if !matches!(why, SplinkError::LayoutChanged) => eprintln!("Refetch failed: {}", why),
+                retried => fetched = retried,
+            }
+        }
+
+        if self.is_suspect_result(&fetched) {
+            let rejected = self.suspect_refreshes.fetch_add(1, Ordering::Relaxed) + 1;
+            if rejected < MAX_SUSPECT_REFRESHES {
+                if let Some(cached) = self.keep_cached() {
+                    eprintln!("Listing still looks incomplete, keeping the {} cached launches", cached.len());
+                    return Ok(cached);
+                }
+            }
+            eprintln!("Accepting the new listing after {} suspect refreshes", rejected);
+        }
+        self.suspect_refreshes.store(0, Ordering::Relaxed);
+
+        let (launches, source) = fetched?;
+
+        let replaced = self.entry.lock().unwrap().replace(CachedLaunches {
+            launches: launches.clone(),
+            source,
+            fetched_at: Instant::now(),
+            fetched_on: Utc::now(),
+        });
+        let keys = |launches: &[FlightData]| launches.iter().map(FlightData::key).collect::<Vec<_>>();
+        if replaced.as_ref().is_none_or(|replaced| keys(&replaced.launches) != keys(&launches)) {
+            self.pages.lock().unwrap().clear();
+        }
+        if let Some(replaced) = replaced {
+            *self.previous.lock().unwrap() = Some(replaced.launches);
+        }
+        Ok(launches)
+    }
+
+    pub fn provenance(&self) -> Option<Provenance> {
+        self.entry.lock().unwrap().as_ref().map(|cached| Provenance {
+            source: cached.source,
+            fetched_on: cached.fetched_on,
+            launch_count: cached.launches.len(),
+            fresh: cached.fetched_at.elapsed() < CACHE_TTL,
+        })
+    }
+
+    pub fn snapshots(&self) -> Option<(Vec<FlightData>, Vec<FlightData>)> {
+        let previous = self.previous.lock().unwrap().clone()?;
+        let current = self.entry.lock().unwrap().as_ref()?.launches.clone();
+        Some((previous, current))
+    }
+
+    pub fn remember_page(&self, user_id: UserId, page: usize) {
+        self.pages.lock().unwrap().insert(user_id, page);
+    }
+
+    pub fn remembered_page(&self, user_id: UserId) -> Option<usize> {
+        self.pages.lock().unwrap().get(&user_id).copied()
+    }
+
+    pub async fn launches(&self) -> Result<Vec<FlightData>, Error> {
+        match self.fresh() {
+            Some(launches) => Ok(launches),
+            None => self.fetch_and_store(false).await,
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct DetailCache {
+    entries: Mutex<HashMap<String, (LaunchDetails, Instant)>>,
+}
+
+impl DetailCache {
+    fn cached(&self, url: &str) -> Option<LaunchDetails> {
+        let mut entries = self.entries.lock().unwrap();
+        entries.retain(|_, (_, fetched_at)| fetched_at.elapsed() < DETAIL_TTL);
+        entries.get(url).map(|(details, _)| details.clone())
+    }
+
+    pub async fn details(&self, source: &dyn DataSource, flight: &FlightData) -> Result<LaunchDetails, Error> {
+        let url = flight.details.as_deref().ok_or("This launch has no details page.")?;
+        if let Some(details) = self.cached(url) {
+            return Ok(details);
+        }
+
+        let details = source.details(flight).await?;
+        self.entries
+            .lock()
+            .unwrap()
+            .insert(url.to_string(), (details.clone(), Instant::now()));
+
+        Ok(details)
+    }
+}
@@ -1,131 +1,2855 @@
-use chrono::{DateTime, NaiveDateTime, Utc};
-use poise::CreateReply;
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::atomic::Ordering;
+use std::time::{Duration, Instant};
+use chrono::{DateTime, TimeDelta, Utc};
+use poise::{ChoiceParameter, CreateReply};
+use poise::futures_util::future::join_all;
+use poise::serenity_prelude as serenity;
 use poise::serenity_prelude::{
-    Colour, ComponentInteractionCollector, CreateActionRow, CreateButton, CreateEmbed,
-    CreateEmbedFooter, CreateInteractionResponse, CreateInteractionResponseMessage
+    AutoArchiveDuration, ButtonStyle, ChannelId, Colour, CreateAttachment, ComponentInteractionCollector, CreateActionRow, CreateButton, CreateEmbed,
+    CreateEmbedAuthor, CreateEmbedFooter, CreateInteractionResponse, CreateInteractionResponseMessage,
+    CreateMessage, CreateSelectMenu, CreateSelectMenuKind, CreateSelectMenuOption, CreateThread, EditInteractionResponse, EditMessage,
+    GuildId, Permissions, UserId
 };
-use scraper::{Html, Selector};
-use scraper::selectable::Selectable;
-use crate::{Error, Context};
+use tokio::sync::Semaphore;
+use crate::{Data, Error, Context, DEFAULT_PREFIX};
+use crate::config::{parse_duration, Config, EmbedField, DEFAULT_EMBED_FIELDS};
+use crate::notifications::test_notification;
+use crate::sources::{fetch_listing_html, MAX_PAGES};
+use crate::store::{QuietHours, Reminder, Watch};
+use crate::timezone::{format_clock, format_dual, is_daylight_saving, parse_clock, Zone};
+use crate::details::LaunchDetails;
+use crate::launches::{canonical_country, provider_for_rocket, FlightData, LaunchSite};
 
-const NEXTSPACEFLIGHT_LINK: &'static str = "https://nextspaceflight.com/launches/";
-const INTERACTION_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(3600);
+const INTERACTION_TIMEOUT: Duration = Duration::from_secs(3600);
+const PRESS_DEBOUNCE: Duration = Duration::from_millis(500);
+const RATE_LIMIT_BACKOFF: Duration = Duration::from_secs(1);
+const PROVIDER_DOMAINS: [(&str, &str); 14] = [
+    ("SpaceX", "spacex.com"),
+    ("United Launch Alliance", "ulalaunch.com"),
+    ("Rocket Lab", "rocketlabusa.com"),
+    ("Blue Origin", "blueorigin.com"),
+    ("Arianespace", "arianespace.com"),
+    ("Northrop Grumman", "northropgrumman.com"),
+    ("Firefly", "fireflyspace.com"),
+    ("NASA", "nasa.gov"),
+    ("ISRO", "isro.gov.in"),
+    ("JAXA", "jaxa.jp"),
+    ("Roscosmos", "roscosmos.ru"),
+    ("CASC", "spacechina.com"),
+    ("Galactic Energy", "galactic-energy.cn"),
+    ("LandSpace", "landspace.com"),
+];
 
-#[derive(Debug, Clone)]
-struct FlightData {
-    name: String,
-    time: DateTime<Utc>,
-    launch_site: String,
-    details: String,
+const TOP_PROVIDERS_DEFAULT: usize = 10;
+const TOP_MISSIONS_DEFAULT: usize = 5;
+const NEXT_COUNT_MAX: usize = 20;
+const SUMMARY_WINDOW: TimeDelta = TimeDelta::hours(24);
+const STREAM_WINDOW_HOURS: i64 = 6;
+const DETAIL_FETCH_CONCURRENCY: usize = 4;
+const LINK_BUTTON_LIMIT: usize = 5;
+const DASHBOARD_REFRESH: Duration = Duration::from_secs(60);
+const DASHBOARD_COUNT: usize = 5;
+const DASHBOARD_MINUTES_DEFAULT: u64 = 30;
+const COUNTDOWN_REFRESH: Duration = Duration::from_secs(30);
+const SELECT_MENU_LIMIT: usize = 25;
+const SELECT_LABEL_LIMIT: usize = 100;
+const PREFIX_MAX_LENGTH: usize = 5;
+const REMINDALL_DEFAULT: usize = 5;
+const REMINDALL_MAX: usize = 10;
+const REMINDER_LEAD_DEFAULT_MINUTES: i64 = 60;
+const BUTTON_LABEL_LIMIT: usize = 80;
+
+const EMBED_TITLE_LIMIT: usize = 256;
+const EMBED_AUTHOR_LIMIT: usize = 256;
+const EMBED_DESCRIPTION_LIMIT: usize = 4096;
+const EMBED_FIELD_NAME_LIMIT: usize = 256;
+const EMBED_FIELD_VALUE_LIMIT: usize = 1024;
+const EMBED_FIELD_COUNT_LIMIT: usize = 25;
+const EMBED_TOTAL_LIMIT: usize = 6000;
+const MESSAGE_CONTENT_LIMIT: usize = 2000;
+const TOP_SUBSCRIBED_PROVIDERS: usize = 10;
+const ATTACHMENT_LIMIT: usize = 8 * 1024 * 1024;
+const UNKNOWN_MESSAGE: isize = 10008;
+
+impl LaunchSite {
+    fn fields(&self) -> Vec<(&'static str, String, bool)> {
+        let country = self.country.as_ref().map(|country| match self.flag() {
+            Some(flag) => format!("{} {}", flag, country),
+            None => country.clone(),
+        });
+
+        [
+            ("Pad", &self.pad),
+            ("Facility", &self.facility),
+            ("Region", &self.region),
+            ("Country", &country),
+        ]
+            .into_iter()
+            .filter_map(|(name, value)| Some((name, truncate(value.as_ref()?, EMBED_FIELD_VALUE_LIMIT), true)))
+            .collect()
+    }
 }
 
 impl FlightData {
-    fn formatted_time(&self) -> String {
-        format!("<t:{}:F>", self.time.timestamp())
+    pub fn formatted_time(&self) -> String {
+        if self.approximate {
+            format!("NET <t:{}:D> (time TBD)", self.time.timestamp())
+        } else {
+            format!("<t:{}:F>", self.time.timestamp())
+        }
+    }
+
+    fn author(&self) -> Option<CreateEmbedAuthor> {
+        let provider = self.provider.trim();
+        if provider.is_empty() {
+            return None;
+        }
+
+        let author = CreateEmbedAuthor::new(truncate(provider, EMBED_AUTHOR_LIMIT));
+        let logo = PROVIDER_DOMAINS
+            .iter()
+            .find(|(name, _)| provider.eq_ignore_ascii_case(name))
+            .map(|(_, domain)| format!("https://www.google.com/s2/favicons?domain={}&sz=64", domain));
+
+        Some(match logo {
+            Some(logo) => author.icon_url(logo),
+            None => author,
+        })
+    }
+
+    fn map_field(&self) -> Option<(&'static str, String, bool)> {
+        let (lat, lon) = self.coordinates()?;
+        Some((
+            "📍 Map",
+            format!("[OpenStreetMap](https://www.openstreetmap.org/?mlat={lat}&mlon={lon}#map=13/{lat}/{lon})"),
+            true,
+        ))
+    }
+
+    fn to_embed(&self, counter: usize, config: &Config) -> CreateEmbed {
+        self.titled_embed(&format!("#{} | {}", counter, self.name.trim()), config.embed_colour, &config.embed_fields)
+    }
+
+    fn embed_fields(&self, fields: &[EmbedField]) -> Vec<(&'static str, String, bool)> {
+        fields
+            .iter()
+            .flat_map(|field| match field {
+                EmbedField::Time => vec![("Time", self.formatted_time(), false)],
+                EmbedField::Site => self.location.fields(),
+                EmbedField::Map => self.map_field().into_iter().collect(),
+                EmbedField::Provider => match self.provider_key() {
+                    "Unknown" => Vec::new(),
+                    provider => vec![("Provider", truncate(provider, EMBED_FIELD_VALUE_LIMIT), true)],
+                },
+                EmbedField::Countdown if !self.approximate => {
+                    vec![("Countdown", format!("<t:{}:R>", self.time.timestamp()), true)]
+                }
+                EmbedField::Countdown => Vec::new(),
+            })
+            .collect()
     }
 
-    fn to_embed(&self, counter: usize) -> CreateEmbed {
-        CreateEmbed::new()
+    fn compact_embed(&self, counter: usize, colour: Colour) -> CreateEmbed {
+        let mut embed = CreateEmbed::new()
             .footer(CreateEmbedFooter::new("Via NextSpaceflight"))
-            .fields(vec![
-                ("Time", self.formatted_time(), false),
-                ("Launch Site", String::from(&self.launch_site), false),
-            ])
-            .title(format!("#{} | {}", counter, self.name.trim()))
-            .url(format!("https://nextspaceflight.com{}", self.details))
-            .color(Colour::new(0xFFFFFF))
-    }
-}
-
-fn parse_time(time_str: &str) -> Option<DateTime<Utc>> {
-    NaiveDateTime::parse_from_str(time_str, "%a %b %d, %Y %H:%M %Z")
-        .ok()
-        .map(|t| DateTime::from_naive_utc_and_offset(t, Utc))
-}
-
-async fn fetch_launches() -> Result<Vec<FlightData>, Error> {
-    let res = reqwest::get(NEXTSPACEFLIGHT_LINK).await?.text().await?;
-    let document = Html::parse_document(&res);
-
-    let mdl_card = Selector::parse(".mdl-card").unwrap();
-    let header = Selector::parse("h5.header-style").unwrap();
-    let launch_location = Selector::parse(".mdl-card__supporting-text").unwrap();
-    let details_button = Selector::parse(".mdc-button").unwrap();
-
-    document
-        .select(&mdl_card)
-        .filter_map(|launch| {
-            let launch_data: Vec<_> = launch
-                .select(&launch_location)
-                .next()?
-                .text()
-                .collect();
+            .title(truncate(&format!("#{} | {}", counter, self.name.trim()), EMBED_TITLE_LIMIT))
+            .description(self.formatted_time())
+            .color(colour);
 
-            if launch_data.len() != 4 {
-                return None;
-            }
+        if let Some(url) = &self.details {
+            embed = embed.url(url);
+        }
+
+        embed
+    }
+
+    pub fn base_embed(&self, colour: Colour) -> CreateEmbed {
+        self.titled_embed(self.name.trim(), colour, &DEFAULT_EMBED_FIELDS)
+    }
+
+    fn titled_embed(&self, title: &str, colour: Colour, fields: &[EmbedField]) -> CreateEmbed {
+        let title = if self.crewed {
+            format!("🧑‍🚀 {}", title)
+        } else {
+            title.to_string()
+        };
+        let title = if self.approximate {
+            format!("TBD | {}", title)
+        } else {
+            title
+        };
+        let (title, colour) = if self.is_live(Utc::now()) {
+            (format!("🔴 LIVE | {}", title), Colour::RED)
+        } else {
+            (title, colour)
+        };
+
+        let mut embed = CreateEmbed::new()
+            .footer(CreateEmbedFooter::new("Via NextSpaceflight"))
+            .fields(self.embed_fields(fields))
+            .title(truncate(&title, EMBED_TITLE_LIMIT))
+            .color(colour);
 
-            let time = parse_time(launch_data[1])?;
+        if !self.approximate {
+            embed = embed.timestamp(self.time);
+        }
+        if let Some(url) = &self.details {
+            embed = embed.url(url);
+        }
+        if let Some(author) = self.author() {
+            embed = embed.author(author);
+        }
 
-            Some(FlightData {
-                name: launch.select(&header).next()?.text().next()?.to_string(),
-                launch_site: launch_data[3].to_string(),
-                time,
-                details: launch.select(&details_button).next()?.value().attr("href")?.to_string(),
+        embed
+    }
+
+    fn details_embed(&self, details: &LaunchDetails, colour: Colour) -> CreateEmbed {
+        let title = truncate(self.name.trim(), EMBED_TITLE_LIMIT);
+        let footer = "Via NextSpaceflight";
+        let mut remaining = EMBED_TOTAL_LIMIT - title.chars().count() - footer.chars().count();
+
+        let provider = details.provider.as_deref().unwrap_or(&self.provider);
+        let fields = [
+            ("Time", Some(self.formatted_time())),
+            ("Provider", Some(provider.to_string()).filter(|provider| !provider.is_empty())),
+            ("Launch Site", Some(match self.location.flag() {
+                Some(flag) => format!("{} {}", flag, self.launch_site),
+                None => self.launch_site.clone(),
+            })),
+            ("Orbit", details.orbit.clone()),
+            ("Weather", details.weather_percentage().map(weather_bar).or_else(|| details.weather.clone())),
+            ("Stream", details.stream.clone()),
+        ]
+            .into_iter()
+            .filter_map(|(name, value)| Some((name, truncate(&value?, EMBED_FIELD_VALUE_LIMIT), false)))
+            .take_while(|(name, value, _)| {
+                let length = name.chars().count() + value.chars().count();
+                remaining.checked_sub(length).map(|left| remaining = left).is_some()
             })
-        })
-        .map(Ok)
-        .collect()
+            .collect::<Vec<_>>();
+
+        let mut embed = CreateEmbed::new()
+            .footer(CreateEmbedFooter::new(footer))
+            .fields(fields)
+            .title(title)
+            .color(colour);
+
+        if let Some(url) = &self.details {
+            embed = embed.url(url);
+        }
+        if let Some(description) = details.description.as_deref().filter(|_| remaining > 0) {
+            embed = embed.description(truncate(description, EMBED_DESCRIPTION_LIMIT.min(remaining)));
+        }
+        if let Some(patch) = &details.patch {
+            embed = embed.image(patch);
+        }
+
+        embed
+    }
+
+    pub fn provider_key(&self) -> &str {
+        match self.provider.trim() {
+            "" => provider_for_rocket(&self.rocket).unwrap_or("Unknown"),
+            provider => provider,
+        }
+    }
 }
 
-#[poise::command(slash_command)]
-pub async fn fetch(ctx: Context<'_>) -> Result<(), Error> {
-    let launches = fetch_launches().await?;
+fn weather_bar(percentage: u8) -> String {
+    let filled = (usize::from(percentage) + 10) / 20;
+    format!("{}{} {}%", "🟩".repeat(filled), "⬜".repeat(5 - filled), percentage)
+}
 
-    let embed_pages: Vec<CreateEmbed> = launches
+fn group_by_provider(launches: &[FlightData]) -> BTreeMap<&str, Vec<&FlightData>> {
+    let mut groups: BTreeMap<&str, Vec<&FlightData>> = BTreeMap::new();
+    for flight in launches {
+        groups.entry(flight.provider_key()).or_default().push(flight);
+    }
+    groups
+}
+
+fn provider_embed(provider: &str, flights: &[&FlightData], colour: Colour) -> CreateEmbed {
+    let fields = flights
         .iter()
-        .enumerate()
-        .map(|(i, flight)| flight.to_embed(i + 1))
-        .collect();
+        .take(EMBED_FIELD_COUNT_LIMIT)
+        .map(|flight| (
+            truncate(flight.name.trim(), EMBED_FIELD_NAME_LIMIT),
+            truncate(&format!("{}\n{}", flight.formatted_time(), flight.launch_site), EMBED_FIELD_VALUE_LIMIT),
+            false,
+        ));
 
-    let ctx_id = ctx.id();
-    let prev_button_id = format!("{}previous", ctx_id);
-    let next_button_id = format!("{}next", ctx_id);
+    CreateEmbed::new()
+        .footer(CreateEmbedFooter::new("Via NextSpaceflight"))
+        .fields(fields)
+        .title(truncate(&format!("{} | {} launches", provider, flights.len()), EMBED_TITLE_LIMIT))
+        .color(colour)
+}
+
+fn find_provider<'a>(
+    groups: &BTreeMap<&'a str, Vec<&'a FlightData>>,
+    query: &str,
+) -> Option<(&'a str, Vec<&'a FlightData>)> {
+    groups
+        .iter()
+        .find(|(provider, _)| provider.eq_ignore_ascii_case(query.trim()))
+        .map(|(provider, flights)| (*provider, flights.clone()))
+}
+
+fn soonest<'a>(flights: &[&'a FlightData], now: DateTime<Utc>) -> Option<&'a FlightData> {
+    flights
+        .iter()
+        .filter(|flight| !flight.approximate && flight.time >= now)
+        .min_by_key(|flight| flight.time)
+        .copied()
+}
+
+fn last_launch_by_provider(launches: &[FlightData], now: DateTime<Utc>) -> BTreeMap<String, DateTime<Utc>> {
+    let mut last_launches: BTreeMap<String, DateTime<Utc>> = BTreeMap::new();
+    for flight in launches.iter().filter(|flight| !flight.approximate && flight.time < now) {
+        let last = last_launches.entry(flight.provider_key().to_string()).or_insert(flight.time);
+        *last = (*last).max(flight.time);
+    }
+    last_launches
+}
+
+fn days_since_field(
+    last_launches: &BTreeMap<String, DateTime<Utc>>,
+    flight: &FlightData,
+    now: DateTime<Utc>,
+) -> Option<(&'static str, String, bool)> {
+    let last = last_launches.get(flight.provider_key())?;
+    let days = (now - *last).num_days();
+    let since = match days {
+        0 => String::from("Earlier today"),
+        1 => String::from("1 day ago"),
+        _ => format!("{} days ago", days),
+    };
+
+    Some(("Provider's last launch", since, true))
+}
+
+pub fn format_countdown(remaining: TimeDelta) -> String {
+    if remaining <= TimeDelta::zero() {
+        return String::from("now");
+    }
+
+    let days = remaining.num_days();
+    let hours = remaining.num_hours() % 24;
+    let minutes = remaining.num_minutes() % 60;
+
+    match (days, hours) {
+        (0, 0) => format!("{}m", minutes),
+        (0, _) => format!("{}h {}m", hours, minutes),
+        _ => format!("{}d {}h {}m", days, hours, minutes),
+    }
+}
+
+fn truncate(s: &str, max: usize) -> String {
+    if s.chars().count() <= max {
+        return s.to_string();
+    }
+
+    let mut truncated: String = s.chars().take(max.saturating_sub(1)).collect();
+    truncated.push('…');
+    truncated
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct ViewOptions {
+    include_past: bool,
+    include_all: bool,
+    newest_first: bool,
+    include_distant: bool,
+}
+
+fn apply_view(config: &Config, launches: &mut Vec<FlightData>, options: ViewOptions) {
+    let now = Utc::now();
+    launches.retain(|flight| {
+        (options.include_past || flight.approximate || flight.time >= now || flight.is_live(now))
+            && (options.include_all || !config.is_blocked(flight))
+            && (options.include_distant || config.horizon.is_none_or(|horizon| flight.time - now <= horizon))
+    });
+
+    sort_launches(launches, options.newest_first, now);
+}
+
+fn sort_launches(launches: &mut [FlightData], newest_first: bool, now: DateTime<Utc>) {
+    launches.sort_by(|a, b| (a.time, a.name.trim(), a.provider.trim()).cmp(&(b.time, b.name.trim(), b.provider.trim())));
+    if newest_first {
+        launches.reverse();
+    }
+    launches.sort_by_key(|flight| (!flight.is_live(now), flight.approximate));
+}
+
+fn default_view(config: &Config, launches: &mut Vec<FlightData>) {
+    apply_view(config, launches, ViewOptions::default());
+}
+
+fn unknown_zone(zone: &str) -> String {
+    let zone = zone.trim();
+    if is_daylight_saving(zone) {
+        format!("`{}` changes with daylight saving, which isn't supported. Use its current UTC offset instead, like UTC-4.", zone)
+    } else {
+        format!("I don't know the timezone `{}`. Try an offset like UTC+2 or UTC-5:30.", zone)
+    }
+}
+
+fn user_zone(ctx: Context<'_>) -> Option<Zone> {
+    ctx.data().store.user(ctx.author().id).timezone.as_deref().and_then(Zone::parse)
+}
+
+fn check_scrape_cooldown(data: &Data, user_id: UserId) -> Result<(), Duration> {
+    let window = data.config.scrape_cooldown;
+    if window.is_zero() {
+        return Ok(());
+    }
+
+    let mut cooldowns = data.scrape_cooldowns.lock().unwrap();
+    cooldowns.retain(|_, scraped_at| scraped_at.elapsed() < window);
+    match cooldowns.get(&user_id) {
+        Some(scraped_at) => Err(window.saturating_sub(scraped_at.elapsed())),
+        None => {
+            cooldowns.insert(user_id, Instant::now());
+            Ok(())
+        }
+    }
+}
+
+fn require_scrape_cooldown(ctx: Context<'_>) -> Result<(), Error> {
+    check_scrape_cooldown(ctx.data(), ctx.author().id).map_err(|remaining| {
+        format!("That needs a fresh scrape, please wait {}s and try again.", remaining.as_secs().max(1)).into()
+    })
+}
+
+async fn scraped_launches(ctx: Context<'_>) -> Result<Vec<FlightData>, Error> {
+    let launches = &ctx.data().launches;
+    if !launches.is_fresh() {
+        require_scrape_cooldown(ctx)?;
+        ctx.defer().await?;
+    }
+    launches.launches().await
+}
+
+fn require_guild(ctx: Context<'_>) -> Result<GuildId, Error> {
+    ctx.guild_id().ok_or_else(|| "This command only works in a server.".into())
+}
 
-    let initial_reply = {
-        let components = CreateActionRow::Buttons(vec![
-            CreateButton::new(&prev_button_id).label("Previous"),
-            CreateButton::new(&next_button_id).label("Next")
-        ]);
+async fn in_allowed_channel(ctx: Context<'_>) -> Result<bool, Error> {
+    let allowed = &ctx.data().config.allowed_channels;
+    if allowed.is_empty() || ctx.guild_id().is_none() || allowed.contains(&ctx.channel_id()) {
+        return Ok(true);
+    }
 
+    ctx.send(
         CreateReply::default()
-            .embed(embed_pages[0].clone())
-            .components(vec![components])
+            .content(format!("Please use this command in <#{}>.", allowed[0]))
+            .ephemeral(true)
+    ).await?;
+
+    Ok(false)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Direction {
+    Previous,
+    Next,
+}
+
+pub fn is_rate_limited(why: &serenity::Error) -> bool {
+    matches!(why, serenity::Error::Http(http) if http.status_code().is_some_and(|status| status.as_u16() == 429))
+}
+
+fn is_unknown_message(why: &serenity::Error) -> bool {
+    matches!(
+        why,
+        serenity::Error::Http(serenity::HttpError::UnsuccessfulRequest(response)) if response.error.code == UNKNOWN_MESSAGE
+    )
+}
+
+fn button_id(ctx_id: u64, name: &str) -> String {
+    format!("{}:{}", ctx_id, name)
+}
+
+fn is_own_button(custom_id: &str, ctx_id: u64) -> bool {
+    custom_id.strip_prefix(&ctx_id.to_string()).is_some_and(|rest| rest.starts_with(':'))
+}
+
+fn next_page(current: usize, total: usize, direction: Direction) -> usize {
+    match direction {
+        Direction::Next => (current + 1) % total,
+        Direction::Previous => current.checked_sub(1).unwrap_or(total - 1),
+    }
+}
+
+async fn paginate(ctx: Context<'_>, embed_pages: Vec<CreateEmbed>) -> Result<(), Error> {
+    paginate_from(ctx, embed_pages, PagerOptions::default(), |_| {}).await
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+enum Expansion {
+    #[default]
+    None,
+    Description,
+    Details,
+}
+
+#[derive(Debug, Default)]
+struct PagerOptions {
+    start: usize,
+    content: Option<String>,
+    channel: Option<ChannelId>,
+    expansion: Expansion,
+    flights: Vec<FlightData>,
+}
+
+async fn expand_page(ctx: Context<'_>, embed_pages: &mut [CreateEmbed], expanded: &mut [bool], options: &PagerOptions, page: usize) {
+    let Some(flight) = options.flights.get(page).filter(|_| !expanded[page]) else {
+        return;
     };
 
-    ctx.send(initial_reply).await?;
+    let data = ctx.data();
+    match data.details.details(data.source.as_ref(), flight).await {
+        Ok(details) => match options.expansion {
+            Expansion::Details => {
+                embed_pages[page] = flight
+                    .details_embed(&details, data.config.embed_colour)
+                    .title(truncate(&format!("#{} | {}", page + 1, flight.name.trim()), EMBED_TITLE_LIMIT));
+            }
+            Expansion::Description => {
+                if let Some(description) = &details.description {
+                    embed_pages[page] = embed_pages[page].clone().description(truncate(description, EMBED_DESCRIPTION_LIMIT));
+                }
+            }
+            Expansion::None => {}
+        },
+        Err(why) => eprintln!("Showing {} without details: {}", flight.name.trim(), why),
+    }
+    expanded[page] = true;
+}
+
+fn expands(options: &PagerOptions, page: usize) -> bool {
+    options.expansion != Expansion::None && page < options.flights.len()
+}
+
+async fn paginate_from(
+    ctx: Context<'_>,
+    mut embed_pages: Vec<CreateEmbed>,
+    mut options: PagerOptions,
+    on_turn: impl Fn(usize),
+) -> Result<(), Error> {
+    let total = embed_pages.len();
+    let max_results = ctx.data().config.max_results;
+    if total > max_results {
+        embed_pages.truncate(max_results);
+        options.flights.truncate(max_results);
+        let note = format!("Showing the first {} of {} results.", max_results, total);
+        options.content = Some(match options.content {
+            Some(content) => format!("{}\n{}", content, note),
+            None => note,
+        });
+    }
+
+    let start = options.start.min(embed_pages.len() - 1);
+    let mut expanded = vec![false; embed_pages.len()];
+    if expands(&options, start) {
+        ctx.defer().await?;
+        expand_page(ctx, &mut embed_pages, &mut expanded, &options, start).await;
+    }
+    let ctx_id = ctx.id();
+    let prev_button_id = button_id(ctx_id, "previous");
+    let next_button_id = button_id(ctx_id, "next");
+
+    let components = CreateActionRow::Buttons(vec![
+        CreateButton::new(&prev_button_id).label("Previous"),
+        CreateButton::new(&next_button_id).label("Next")
+    ]);
+
+    match options.channel {
+        Some(channel) => {
+            let mut message = CreateMessage::new()
+                .embed(embed_pages[start].clone())
+                .components(vec![components])
+                .allowed_mentions(serenity::CreateAllowedMentions::new());
+            if let Some(content) = options.content.take() {
+                message = message.content(content);
+            }
+            channel.send_message(ctx, message).await?;
+        }
+        None => {
+            let mut reply = CreateReply::default()
+                .embed(embed_pages[start].clone())
+                .components(vec![components]);
+            if let Some(content) = options.content.take() {
+                reply = reply.content(content);
+            }
+            ctx.send(reply).await?;
+        }
+    }
 
-    let mut page_num = 0;
+    let mut page_num = start;
+    let mut last_handled: Option<Instant> = None;
     while let Some(press) = ComponentInteractionCollector::new(ctx)
-        .filter(move |press| press.data.custom_id.starts_with(&ctx_id.to_string()))
+        .filter(move |press| is_own_button(&press.data.custom_id, ctx_id))
         .timeout(INTERACTION_TIMEOUT)
         .await
     {
-        let total = embed_pages.len();
-        page_num = match press.data.custom_id.as_str() {
-            id if id == next_button_id => (page_num + 1) % total,
-            id if id == prev_button_id => page_num.checked_sub(1).unwrap_or(total - 1),
-            _ => page_num,
+        if last_handled.is_some_and(|handled| handled.elapsed() < PRESS_DEBOUNCE) {
+            let _ = press.create_response(ctx.serenity_context(), CreateInteractionResponse::Acknowledge).await;
+            continue;
+        }
+
+        let direction = match press.data.custom_id.as_str() {
+            id if id == next_button_id => Direction::Next,
+            id if id == prev_button_id => Direction::Previous,
+            _ => continue,
         };
+        let target = next_page(page_num, embed_pages.len(), direction);
 
-        press.create_response(
-            ctx.serenity_context(),
-            CreateInteractionResponse::UpdateMessage(
-                CreateInteractionResponseMessage::new()
-                    .embed(embed_pages[page_num].clone())
-            )
-        ).await?;
+        let response = if expands(&options, target) && !expanded[target] {
+            match press.create_response(ctx.serenity_context(), CreateInteractionResponse::Acknowledge).await {
+                Ok(()) => {
+                    expand_page(ctx, &mut embed_pages, &mut expanded, &options, target).await;
+                    press
+                        .edit_response(ctx, EditInteractionResponse::new().embed(embed_pages[target].clone()))
+                        .await
+                        .map(|_| ())
+                }
+                Err(why) => Err(why),
+            }
+        } else {
+            press.create_response(
+                ctx.serenity_context(),
+                CreateInteractionResponse::UpdateMessage(
+                    CreateInteractionResponseMessage::new()
+                        .embed(embed_pages[target].clone())
+                )
+            ).await
+        };
+
+        match response {
+            Ok(()) => {
+                page_num = target;
+                on_turn(page_num);
+            }
+            Err(why) if is_rate_limited(&why) => tokio::time::sleep(RATE_LIMIT_BACKOFF).await,
+            Err(why) if is_unknown_message(&why) => {
+                println!("Ending pagination, its message is gone: {}", why);
+                break;
+            }
+            Err(why) => eprintln!("Ignoring failed page turn: {}", why),
+        }
+        last_handled = Some(Instant::now());
+    }
+
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ChoiceParameter)]
+pub enum Verbosity {
+    Compact,
+    Normal,
+    Detailed,
+}
+
+/// List upcoming launches, one page per launch
+#[allow(clippy::too_many_arguments)]
+#[poise::command(slash_command, check = "in_allowed_channel")]
+pub async fn fetch(
+    ctx: Context<'_>,
+    #[description = "Show one page per launch provider"] grouped: Option<bool>,
+    #[description = "Include launches that have already happened"] include_past: Option<bool>,
+    #[description = "Include launches hidden by the blocklist"] include_all: Option<bool>,
+    #[description = "Show the furthest-out launches first"] newest_first: Option<bool>,
+    #[description = "Resume at the page you last viewed"] resume: Option<bool>,
+    #[description = "How many NextSpaceflight result pages to scrape"]
+    #[min = 1]
+    #[max = 5]
+    depth: Option<u8>,
+    #[description = "Post the list in a new thread"] thread: Option<bool>,
+    #[description = "How much detail to show for each launch"] verbosity: Option<Verbosity>,
+    #[description = "Include launches beyond the configured horizon"] include_distant: Option<bool>,
+    #[description = "Show each launch's mission description"] descriptions: Option<bool>,
+) -> Result<(), Error> {
+    let verbosity = verbosity.unwrap_or(Verbosity::Normal);
+    let depth = usize::from(depth.unwrap_or(1)).clamp(1, MAX_PAGES);
+    let (mut launches, scraped) = if depth > 1 {
+        require_scrape_cooldown(ctx)?;
+        ctx.defer().await?;
+        let (launches, scraped) = ctx.data().source.fetch_pages(depth).await?;
+        (launches, Some(scraped))
+    } else {
+        (scraped_launches(ctx).await?, None)
+    };
+    let now = Utc::now();
+    let last_launches = last_launch_by_provider(&launches, now);
+    apply_view(&ctx.data().config, &mut launches, ViewOptions {
+        include_past: include_past.unwrap_or(false),
+        include_all: include_all.unwrap_or(false),
+        newest_first: newest_first.unwrap_or(false),
+        include_distant: include_distant.unwrap_or(false),
+    });
+
+    if launches.is_empty() {
+        ctx.say("No launches found.").await?;
+        return Ok(());
+    }
+
+    let embed_pages: Vec<CreateEmbed> = if grouped.unwrap_or(false) {
+        group_by_provider(&launches)
+            .iter()
+            .map(|(provider, flights)| provider_embed(provider, flights, ctx.data().config.embed_colour))
+            .collect()
+    } else if verbosity == Verbosity::Compact {
+        launches
+            .iter()
+            .enumerate()
+            .map(|(i, flight)| flight.compact_embed(i + 1, ctx.data().config.embed_colour))
+            .collect()
+    } else {
+        launches
+            .iter()
+            .enumerate()
+            .map(|(i, flight)| flight.to_embed(i + 1, &ctx.data().config).fields(days_since_field(&last_launches, flight, now)))
+            .collect()
+    };
+    let expansion = match (verbosity, descriptions.unwrap_or(false)) {
+        _ if grouped.unwrap_or(false) => Expansion::None,
+        (Verbosity::Detailed, _) => Expansion::Details,
+        (_, true) => Expansion::Description,
+        _ => Expansion::None,
+    };
+
+    let user_id = ctx.author().id;
+    let cache = &ctx.data().launches;
+    let start = match resume {
+        Some(true) => cache.remembered_page(user_id).unwrap_or(0),
+        _ => 0,
+    };
+
+    let mut notes: Vec<String> = scraped.map(|scraped| format!("Scraped {} result page(s).", scraped)).into_iter().collect();
+    let channel = match thread {
+        Some(true) => match start_thread(ctx).await {
+            Ok(channel) => Some(channel),
+            Err(why) => {
+                notes.push(format!("Couldn't start a thread ({}), so here's the list.", why));
+                None
+            }
+        },
+        _ => None,
+    };
+
+    let options = PagerOptions {
+        start,
+        content: (!notes.is_empty()).then(|| notes.join("\n")),
+        channel,
+        expansion,
+        flights: launches,
+    };
+
+    paginate_from(ctx, embed_pages, options, |page| cache.remember_page(user_id, page)).await
+}
+
+async fn start_thread(ctx: Context<'_>) -> Result<ChannelId, Error> {
+    require_guild(ctx)?;
+
+    let mut message = ctx
+        .send(CreateReply::default().content("🧵 Upcoming launches are in the thread below."))
+        .await?
+        .into_message()
+        .await?;
+    let thread = ctx
+        .channel_id()
+        .create_thread_from_message(
+            ctx,
+            message.id,
+            CreateThread::new("Upcoming launches").auto_archive_duration(AutoArchiveDuration::OneHour),
+        )
+        .await;
+
+    match thread {
+        Ok(thread) => Ok(thread.id),
+        Err(why) => {
+            let _ = message.edit(ctx, EditMessage::new().content("🧵 Couldn't start a thread.")).await;
+            Err(why.into())
+        }
+    }
+}
+
+/// Compare upcoming launches from two providers
+#[poise::command(slash_command, check = "in_allowed_channel")]
+pub async fn compareproviders(
+    ctx: Context<'_>,
+    #[description = "First launch provider"] first: String,
+    #[description = "Second launch provider"] second: String,
+) -> Result<(), Error> {
+    let launches = scraped_launches(ctx).await?;
+    let groups = group_by_provider(&launches);
+    let now = Utc::now();
+
+    let mut embed = CreateEmbed::new()
+        .footer(CreateEmbedFooter::new("Via NextSpaceflight"))
+        .title("Provider comparison")
+        .color(ctx.data().config.embed_colour);
+
+    for query in [&first, &second] {
+        let Some((provider, flights)) = find_provider(&groups, query) else {
+            ctx.say(format!("No launches found for provider `{}`.", query.trim())).await?;
+            return Ok(());
+        };
+
+        let upcoming = flights.iter().filter(|flight| flight.time >= now).count();
+        let next = match soonest(&flights, now) {
+            Some(flight) => format!(
+                "{}\n{} (in {})",
+                flight.name.trim(),
+                flight.formatted_time(),
+                format_countdown(flight.time - now)
+            ),
+            None => String::from("Nothing upcoming"),
+        };
+
+        embed = embed.field(
+            truncate(provider, EMBED_FIELD_NAME_LIMIT),
+            truncate(&format!("Upcoming launches: {}\nNext: {}", upcoming, next), EMBED_FIELD_VALUE_LIMIT),
+            true,
+        );
+    }
+
+    ctx.send(CreateReply::default().embed(embed)).await?;
+
+    Ok(())
+}
+
+/// Check that the bot is responding
+#[poise::command(slash_command)]
+pub async fn ping(ctx: Context<'_>) -> Result<(), Error> {
+    let gateway = ctx.ping().await;
+
+    let started = Instant::now();
+    let reply = ctx.send(CreateReply::default().embed(ping_embed("🏓 Pinging...", ctx.data().config.embed_colour))).await?;
+    let api = started.elapsed();
+
+    reply.edit(ctx, CreateReply::default().embed(ping_embed(&format!(
+        "🏓 Pong! Gateway: {}ms, API: {}ms",
+        gateway.as_millis(),
+        api.as_millis()
+    ), ctx.data().config.embed_colour))).await?;
+
+    Ok(())
+}
+
+fn ping_embed(description: &str, colour: Colour) -> CreateEmbed {
+    CreateEmbed::new()
+        .description(description)
+        .color(colour)
+}
+
+/// Show the details page for a launch
+#[poise::command(slash_command, check = "in_allowed_channel")]
+pub async fn launchdetails(
+    ctx: Context<'_>,
+    #[description = "Launch number as shown by /fetch"] index: usize,
+) -> Result<(), Error> {
+    let mut launches = scraped_launches(ctx).await?;
+    default_view(&ctx.data().config, &mut launches);
+
+    let Some(flight) = index.checked_sub(1).and_then(|i| launches.get(i)) else {
+        ctx.say(format!("There is no launch #{}. Pick a number between 1 and {}.", index, launches.len())).await?;
+        return Ok(());
+    };
+
+    ctx.defer().await?;
+    ctx.send(CreateReply::default().embed(launch_details_embed(ctx, flight).await)).await?;
+
+    Ok(())
+}
+
+async fn launch_details_embed(ctx: Context<'_>, flight: &FlightData) -> CreateEmbed {
+    let data = ctx.data();
+    match data.details.details(data.source.as_ref(), flight).await {
+        Ok(details) => flight.details_embed(&details, data.config.embed_colour),
+        Err(why) => CreateEmbed::new()
+            .title(truncate(flight.name.trim(), EMBED_TITLE_LIMIT))
+            .description(format!("Couldn't load the launch details page: {}", why))
+            .color(Colour::RED),
+    }
+}
+
+fn match_rank(name: &str, query: &str) -> Option<u8> {
+    let name = name.trim().to_lowercase();
+    if name == query {
+        Some(0)
+    } else if name.starts_with(query) {
+        Some(1)
+    } else if name.contains(query) {
+        Some(2)
+    } else if query.split_whitespace().all(|word| name.contains(word)) {
+        Some(3)
+    } else {
+        None
+    }
+}
+
+async fn bot_can_send(ctx: Context<'_>, guild_id: GuildId, channel_id: ChannelId) -> Result<bool, Error> {
+    let member = guild_id.member(ctx, ctx.framework().bot_id).await?;
+    let Some(channel) = channel_id.to_channel(ctx).await?.guild() else {
+        return Ok(false);
+    };
+
+    let permissions = match ctx.guild() {
+        Some(guild) => guild.user_permissions_in(&channel, &member),
+        None => return Ok(false),
+    };
+
+    Ok(permissions.contains(Permissions::VIEW_CHANNEL | Permissions::SEND_MESSAGES | Permissions::EMBED_LINKS))
+}
+
+/// Post launch announcements in this channel
+#[poise::command(slash_command, required_permissions = "MANAGE_GUILD")]
+pub async fn setchannel(
+    ctx: Context<'_>,
+    #[description = "Channel for launch announcements (defaults to this one)"] channel: Option<ChannelId>,
+) -> Result<(), Error> {
+    let guild_id = require_guild(ctx)?;
+    let channel = channel.unwrap_or(ctx.channel_id());
+
+    if !bot_can_send(ctx, guild_id, channel).await? {
+        ctx.say(format!("I can't send embeds in <#{}>, so check my channel permissions and try again.", channel))
+            .await?;
+        return Ok(());
+    }
+
+    ctx.data().store.update_guild(guild_id, |settings| settings.announcement_channel = Some(channel))?;
+    ctx.say(format!("Launch announcements will be posted in <#{}>.", channel)).await?;
+
+    Ok(())
+}
+
+/// Stop posting launch announcements in this server
+#[poise::command(slash_command, required_permissions = "MANAGE_GUILD")]
+pub async fn unsetchannel(ctx: Context<'_>) -> Result<(), Error> {
+    let guild_id = require_guild(ctx)?;
+    if ctx.data().store.guild(guild_id).announcement_channel.is_none() {
+        ctx.say("Launch announcements aren't enabled for this server.").await?;
+        return Ok(());
+    }
+
+    ctx.data().store.update_guild(guild_id, |settings| settings.announcement_channel = None)?;
+    ctx.say("Launch announcements are now disabled for this server.").await?;
+
+    Ok(())
+}
+
+/// Show the next launch from a provider
+#[poise::command(slash_command, check = "in_allowed_channel")]
+pub async fn nextfor(
+    ctx: Context<'_>,
+    #[description = "Launch provider, e.g. SpaceX"] provider: String,
+) -> Result<(), Error> {
+    let mut launches = scraped_launches(ctx).await?;
+    default_view(&ctx.data().config, &mut launches);
+
+    let now = Utc::now();
+    let groups = group_by_provider(&launches);
+    let Some(flight) = find_provider(&groups, &provider).and_then(|(_, flights)| soonest(&flights, now)) else {
+        ctx.say(format!(
+            "No upcoming launches for {}. Use `/agencies` to see valid names.",
+            provider.trim()
+        )).await?;
+        return Ok(());
+    };
+
+    let embed = flight
+        .base_embed(ctx.data().config.embed_colour)
+        .field("Countdown", format!("T-{}", format_countdown(flight.time - now)), false);
+
+    ctx.send(CreateReply::default().embed(embed)).await?;
+
+    Ok(())
+}
+
+/// List launch providers with upcoming launches
+#[poise::command(slash_command, check = "in_allowed_channel")]
+pub async fn agencies(ctx: Context<'_>) -> Result<(), Error> {
+    let mut launches = scraped_launches(ctx).await?;
+    default_view(&ctx.data().config, &mut launches);
+
+    if launches.is_empty() {
+        ctx.say("No launches found.").await?;
+        return Ok(());
+    }
+
+    let listing = group_by_provider(&launches)
+        .iter()
+        .map(|(provider, flights)| format!("**{}**: {}", provider, flights.len()))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let embed = CreateEmbed::new()
+        .footer(CreateEmbedFooter::new("Via NextSpaceflight"))
+        .title("Launch providers")
+        .description(truncate(&listing, EMBED_DESCRIPTION_LIMIT))
+        .color(ctx.data().config.embed_colour);
+
+    ctx.send(CreateReply::default().embed(embed)).await?;
+
+    Ok(())
+}
+
+#[poise::command(prefix_command, slash_command, owners_only, hide_in_help)]
+pub async fn register(ctx: Context<'_>) -> Result<(), Error> {
+    poise::builtins::register_application_commands_buttons(ctx).await?;
+    Ok(())
+}
+
+fn summary_embed(launches: &[FlightData], now: DateTime<Utc>, colour: Colour) -> CreateEmbed {
+    let upcoming: Vec<&FlightData> = launches
+        .iter()
+        .filter(|flight| !flight.approximate && flight.time >= now && flight.time - now <= SUMMARY_WINDOW)
+        .collect();
+
+    let embed = CreateEmbed::new()
+        .footer(CreateEmbedFooter::new("Via NextSpaceflight"))
+        .title("Launches in the next 24 hours")
+        .color(colour);
+
+    if upcoming.is_empty() {
+        let note = match launches.iter().filter(|flight| !flight.approximate && flight.time >= now).min_by_key(|flight| flight.time) {
+            Some(next) => format!(
+                "Nothing in 24h — next is {} in {}.",
+                next.name.trim(),
+                format_countdown(next.time - now)
+            ),
+            None => String::from("Nothing in 24h, and nothing else scheduled."),
+        };
+        return embed.description(truncate(&note, EMBED_DESCRIPTION_LIMIT));
     }
 
+    embed.fields(upcoming.iter().take(EMBED_FIELD_COUNT_LIMIT).map(|flight| (
+        truncate(flight.name.trim(), EMBED_FIELD_NAME_LIMIT),
+        truncate(
+            &format!("In {} ({})\n{}", format_countdown(flight.time - now), flight.formatted_time(), flight.provider_key()),
+            EMBED_FIELD_VALUE_LIMIT,
+        ),
+        false,
+    )))
+}
+
+/// Summarise the launches in the next 24 hours
+#[poise::command(slash_command, check = "in_allowed_channel")]
+pub async fn summary(ctx: Context<'_>) -> Result<(), Error> {
+    let mut launches = scraped_launches(ctx).await?;
+    default_view(&ctx.data().config, &mut launches);
+
+    ctx.send(CreateReply::default().embed(summary_embed(&launches, Utc::now(), ctx.data().config.embed_colour))).await?;
+
     Ok(())
-}
\ No newline at end of file
+}
+
+fn compact_embed(launches: &[FlightData], count: usize, zone: Option<&Zone>, colour: Colour) -> CreateEmbed {
+    let mut listing = String::new();
+    for (i, flight) in launches.iter().take(count).enumerate() {
+        let time = match zone {
+            _ if flight.approximate => flight.formatted_time(),
+            Some(zone) => format!("{} · <t:{}:R>", format_dual(flight.time, Some(zone)), flight.time.timestamp()),
+            None => format!("<t:{}:R>", flight.time.timestamp()),
+        };
+        let line = format!(
+            "`#{}` **{}** — {} · {}\n",
+            i + 1,
+            flight.name.trim(),
+            time,
+            flight.provider_key()
+        );
+        if listing.chars().count() + line.chars().count() > EMBED_DESCRIPTION_LIMIT {
+            break;
+        }
+        listing.push_str(&line);
+    }
+
+    CreateEmbed::new()
+        .footer(CreateEmbedFooter::new("Via NextSpaceflight"))
+        .title("Next launches")
+        .description(listing)
+        .color(colour)
+}
+
+/// Show the next few launches
+#[poise::command(slash_command, check = "in_allowed_channel")]
+pub async fn next(
+    ctx: Context<'_>,
+    #[description = "How many launches to list"]
+    #[min = 1]
+    #[max = 20]
+    count: Option<usize>,
+    #[description = "Also show times in this zone, e.g. UTC-4 or UTC+2"] timezone: Option<String>,
+) -> Result<(), Error> {
+    let zone = match timezone.as_deref().map(|timezone| (timezone, Zone::parse(timezone))) {
+        Some((timezone, None)) => {
+            ctx.say(unknown_zone(timezone)).await?;
+            return Ok(());
+        }
+        Some((_, zone)) => zone,
+        None => user_zone(ctx),
+    };
+
+    let mut launches = scraped_launches(ctx).await?;
+    default_view(&ctx.data().config, &mut launches);
+
+    if launches.is_empty() {
+        ctx.say("No launches found.").await?;
+        return Ok(());
+    }
+
+    let guild_default = ctx.guild_id().and_then(|guild_id| ctx.data().store.guild(guild_id).next_count);
+    let count = count
+        .or(guild_default)
+        .unwrap_or(ctx.data().config.next_count)
+        .clamp(1, NEXT_COUNT_MAX);
+
+    ctx.send(CreateReply::default().embed(compact_embed(&launches, count, zone.as_ref(), ctx.data().config.embed_colour))).await?;
+
+    Ok(())
+}
+
+/// Set how many launches /next shows in this server
+#[poise::command(slash_command, required_permissions = "MANAGE_GUILD")]
+pub async fn setnextcount(
+    ctx: Context<'_>,
+    #[description = "Default number of launches for /next (leave empty to reset)"]
+    #[min = 1]
+    #[max = 20]
+    count: Option<usize>,
+) -> Result<(), Error> {
+    let guild_id = require_guild(ctx)?;
+    let count = count.map(|count| count.clamp(1, NEXT_COUNT_MAX));
+
+    ctx.data().store.update_guild(guild_id, |settings| settings.next_count = count)?;
+
+    ctx.say(match count {
+        Some(count) => format!("`/next` will list {} launches by default.", count),
+        None => format!("`/next` will use the default of {} launches.", ctx.data().config.next_count.clamp(1, NEXT_COUNT_MAX)),
+    }).await?;
+
+    Ok(())
+}
+
+/// Pick a role to ping with launch announcements
+#[poise::command(slash_command, required_permissions = "MANAGE_GUILD")]
+pub async fn setannouncerole(
+    ctx: Context<'_>,
+    #[description = "Role to ping with launch announcements (leave empty to stop pinging)"] role: Option<serenity::Role>,
+) -> Result<(), Error> {
+    let guild_id = require_guild(ctx)?;
+    let role_id = role.as_ref().map(|role| role.id);
+
+    if role.as_ref().is_some_and(|role| role.id.get() == guild_id.get()) {
+        ctx.say("I won't ping @everyone. Pick a dedicated alerts role instead.").await?;
+        return Ok(());
+    }
+
+    ctx.data().store.update_guild(guild_id, |settings| settings.announcement_role = role_id)?;
+
+    let reply = match &role {
+        Some(role) => format!("Launch announcements will ping <@&{}>.", role.id),
+        None => String::from("Launch announcements will no longer ping a role."),
+    };
+    ctx.send(CreateReply::default().content(reply).allowed_mentions(serenity::CreateAllowedMentions::new())).await?;
+
+    Ok(())
+}
+
+fn valid_prefix(prefix: &str) -> bool {
+    (1..=PREFIX_MAX_LENGTH).contains(&prefix.chars().count())
+        && !prefix.chars().any(char::is_whitespace)
+        && !prefix.starts_with(['/', '@', '<', '#'])
+}
+
+/// Change the text command prefix for this server
+#[poise::command(slash_command, required_permissions = "MANAGE_GUILD")]
+pub async fn setprefix(
+    ctx: Context<'_>,
+    #[description = "Text command prefix for this server (leave empty to reset)"] prefix: Option<String>,
+) -> Result<(), Error> {
+    let guild_id = require_guild(ctx)?;
+    let prefix = prefix.map(|prefix| prefix.trim().to_string());
+
+    if let Some(prefix) = prefix.as_deref().filter(|prefix| !valid_prefix(prefix)) {
+        ctx.say(format!(
+            "`{}` can't be used as a prefix. Use up to {} characters without spaces, not starting with /, @, < or #.",
+            prefix, PREFIX_MAX_LENGTH
+        )).await?;
+        return Ok(());
+    }
+
+    ctx.data().store.update_guild(guild_id, |settings| settings.prefix = prefix.clone())?;
+
+    ctx.say(match prefix {
+        Some(prefix) => format!("Text commands now use `{}` in this server.", prefix),
+        None => format!("Text commands now use the default `{}` prefix.", DEFAULT_PREFIX),
+    }).await?;
+
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ChoiceParameter)]
+pub enum Coast {
+    East,
+    West,
+}
+
+impl Coast {
+    fn regions(self) -> &'static [&'static str] {
+        match self {
+            Coast::East => &["florida", "fl", "virginia", "va"],
+            Coast::West => &["california", "ca"],
+        }
+    }
+
+    fn contains(self, site: &LaunchSite) -> bool {
+        let in_usa = site.country.as_deref().is_some_and(|country| {
+            matches!(country.to_lowercase().as_str(), "usa" | "us" | "united states")
+        });
+        let region = site.region.as_deref().unwrap_or_default().to_lowercase();
+
+        in_usa && self.regions().contains(&region.as_str())
+    }
+}
+
+/// Show launches from the US East or West Coast
+#[poise::command(slash_command, check = "in_allowed_channel")]
+pub async fn coast(
+    ctx: Context<'_>,
+    #[description = "US coast to show launches from"] coast: Coast,
+) -> Result<(), Error> {
+    let mut launches = scraped_launches(ctx).await?;
+    default_view(&ctx.data().config, &mut launches);
+    launches.retain(|flight| coast.contains(&flight.location));
+
+    if launches.is_empty() {
+        ctx.say(format!("No upcoming {} Coast launches found.", coast.name())).await?;
+        return Ok(());
+    }
+
+    let embed_pages = launches
+        .iter()
+        .enumerate()
+        .map(|(i, flight)| flight.to_embed(i + 1, &ctx.data().config))
+        .collect();
+
+    paginate(ctx, embed_pages).await
+}
+
+/// Show launches from a country
+#[poise::command(slash_command, check = "in_allowed_channel")]
+pub async fn country(
+    ctx: Context<'_>,
+    #[description = "Country to show launches from, e.g. USA, China or Japan"] country: String,
+) -> Result<(), Error> {
+    let mut launches = scraped_launches(ctx).await?;
+    default_view(&ctx.data().config, &mut launches);
+
+    let wanted = canonical_country(&country);
+    let present: BTreeSet<String> = launches.iter().filter_map(|flight| flight.location.canonical_country()).collect();
+    launches.retain(|flight| {
+        flight
+            .location
+            .canonical_country()
+            .is_some_and(|country| country.eq_ignore_ascii_case(&wanted))
+    });
+
+    if launches.is_empty() {
+        let known = if present.is_empty() {
+            String::from("None of the current launches list a country.")
+        } else {
+            format!("Countries with upcoming launches: {}.", present.into_iter().collect::<Vec<_>>().join(", "))
+        };
+        ctx.say(format!("No upcoming launches found from {}. {}", country.trim(), known)).await?;
+        return Ok(());
+    }
+
+    let embed_pages = launches
+        .iter()
+        .enumerate()
+        .map(|(i, flight)| flight.to_embed(i + 1, &ctx.data().config))
+        .collect();
+
+    paginate(ctx, embed_pages).await
+}
+
+/// Get a DM before launches from a provider
+#[poise::command(slash_command, check = "in_allowed_channel")]
+pub async fn subscribe(
+    ctx: Context<'_>,
+    #[description = "Launch provider to be notified about, e.g. SpaceX"] provider: String,
+) -> Result<(), Error> {
+    let launches = scraped_launches(ctx).await?;
+    let groups = group_by_provider(&launches);
+    let Some((provider, _)) = find_provider(&groups, &provider) else {
+        ctx.say(format!("I don't know a provider called {}. Use `/agencies` to see valid names.", provider.trim())).await?;
+        return Ok(());
+    };
+
+    let user_id = ctx.author().id;
+    if ctx.data().store.user(user_id).is_subscribed(provider) {
+        ctx.say(format!("You're already subscribed to {}.", provider)).await?;
+        return Ok(());
+    }
+
+    ctx.data().store.update_user(user_id, |settings| settings.subscriptions.push(provider.to_string()))?;
+    ctx.say(format!("I'll DM you an hour before each {} launch.", provider)).await?;
+
+    Ok(())
+}
+
+/// Stop launch DMs for a provider
+#[poise::command(slash_command)]
+pub async fn unsubscribe(
+    ctx: Context<'_>,
+    #[description = "Launch provider to stop notifications for"] provider: String,
+) -> Result<(), Error> {
+    let user_id = ctx.author().id;
+    if !ctx.data().store.user(user_id).is_subscribed(&provider) {
+        ctx.say(format!("You aren't subscribed to {}.", provider.trim())).await?;
+        return Ok(());
+    }
+
+    ctx.data().store.update_user(user_id, |settings| {
+        settings.subscriptions.retain(|subscription| !subscription.eq_ignore_ascii_case(provider.trim()))
+    })?;
+    ctx.say(format!("You'll no longer be notified about {}.", provider.trim())).await?;
+
+    Ok(())
+}
+
+/// Show upcoming launches from the providers you're subscribed to
+#[poise::command(slash_command, check = "in_allowed_channel")]
+pub async fn watchlist(ctx: Context<'_>) -> Result<(), Error> {
+    let settings = ctx.data().store.user(ctx.author().id);
+    if settings.subscriptions.is_empty() {
+        ctx.say("You're not subscribed to any providers yet. Use `/subscribe` to build your watchlist.").await?;
+        return Ok(());
+    }
+
+    let mut launches = scraped_launches(ctx).await?;
+    default_view(&ctx.data().config, &mut launches);
+    launches.retain(|flight| settings.is_subscribed(flight.provider_key()));
+
+    if launches.is_empty() {
+        ctx.say(format!("None of your providers ({}) have upcoming launches.", settings.subscriptions.join(", "))).await?;
+        return Ok(());
+    }
+
+    let embed_pages = launches
+        .iter()
+        .enumerate()
+        .map(|(i, flight)| flight.to_embed(i + 1, &ctx.data().config))
+        .collect();
+
+    paginate(ctx, embed_pages).await
+}
+
+/// Manage your launch subscriptions
+#[poise::command(
+    slash_command,
+    subcommands("subscriptions_list", "subscriptions_test", "subscriptions_list_all"),
+    subcommand_required
+)]
+pub async fn subscriptions(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// List your subscriptions and watched launches
+#[poise::command(slash_command, rename = "list")]
+pub async fn subscriptions_list(ctx: Context<'_>) -> Result<(), Error> {
+    let settings = ctx.data().store.user(ctx.author().id);
+    let mut reply = if settings.subscriptions.is_empty() {
+        String::from("You have no subscriptions. Use `/subscribe` to add one.")
+    } else {
+        format!("You're subscribed to: {}", settings.subscriptions.join(", "))
+    };
+    if !settings.watches.is_empty() {
+        let watches = settings.watches.iter().map(|watch| watch.name.as_str()).collect::<Vec<_>>();
+        reply.push_str(&format!("\nWatching: {}", watches.join(", ")));
+    }
+
+    ctx.send(CreateReply::default().content(reply).ephemeral(true)).await?;
+
+    Ok(())
+}
+
+/// Show subscription stats across all users
+#[poise::command(slash_command, rename = "list-all", owners_only)]
+pub async fn subscriptions_list_all(ctx: Context<'_>) -> Result<(), Error> {
+    let users = ctx.data().store.users();
+    let subscribers: Vec<&Vec<String>> = users
+        .iter()
+        .map(|(_, settings)| &settings.subscriptions)
+        .filter(|subscriptions| !subscriptions.is_empty())
+        .collect();
+
+    let mut providers: BTreeMap<String, (&str, usize)> = BTreeMap::new();
+    for subscription in subscribers.iter().copied().flatten() {
+        providers.entry(subscription.to_lowercase()).or_insert((subscription, 0)).1 += 1;
+    }
+    let mut top: Vec<(&str, usize)> = providers.into_values().collect();
+    top.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+
+    let total: usize = subscribers.iter().map(|subscriptions| subscriptions.len()).sum();
+    let most = subscribers.iter().map(|subscriptions| subscriptions.len()).max().unwrap_or_default();
+    let top = top
+        .iter()
+        .take(TOP_SUBSCRIBED_PROVIDERS)
+        .map(|(provider, count)| format!("{} — {}", provider, count))
+        .collect::<Vec<_>>();
+
+    let embed = CreateEmbed::new()
+        .title("Subscriptions")
+        .fields([
+            ("Total subscriptions", total.to_string(), true),
+            ("Subscribed users", subscribers.len().to_string(), true),
+            ("Most by one user", most.to_string(), true),
+            ("Top providers", if top.is_empty() { String::from("None") } else { top.join("\n") }, false),
+        ])
+        .color(ctx.data().config.embed_colour);
+
+    ctx.send(CreateReply::default().embed(embed).ephemeral(true)).await?;
+
+    Ok(())
+}
+
+/// Send yourself a test notification
+#[poise::command(slash_command, rename = "test")]
+pub async fn subscriptions_test(ctx: Context<'_>) -> Result<(), Error> {
+    let settings = ctx.data().store.user(ctx.author().id);
+    if settings.subscriptions.is_empty() {
+        ctx.send(CreateReply::default().content("You have no subscriptions. Use `/subscribe` to add one.").ephemeral(true)).await?;
+        return Ok(());
+    }
+
+    let now = Utc::now();
+    let launches = scraped_launches(ctx).await?;
+    let Some(flight) = launches
+        .iter()
+        .filter(|flight| !flight.approximate && flight.time >= now && settings.is_subscribed(flight.provider_key()))
+        .min_by_key(|flight| flight.time)
+    else {
+        ctx.send(CreateReply::default().content("None of your subscribed providers have an upcoming launch with an exact time.").ephemeral(true)).await?;
+        return Ok(());
+    };
+
+    let reply = match ctx.author().direct_message(ctx, test_notification(flight, now, ctx.data().config.embed_colour)).await {
+        Ok(_) => String::from("Sent you a test notification."),
+        Err(why) => format!(
+            "I couldn't DM you ({}). Check that direct messages from server members are allowed.",
+            why
+        ),
+    };
+
+    ctx.send(CreateReply::default().content(reply).ephemeral(true)).await?;
+
+    Ok(())
+}
+
+/// Show launches on a rocket family
+#[poise::command(slash_command, check = "in_allowed_channel")]
+pub async fn byrocket(
+    ctx: Context<'_>,
+    #[description = "Rocket name, e.g. Falcon 9"] rocket: String,
+) -> Result<(), Error> {
+    let mut launches = scraped_launches(ctx).await?;
+    default_view(&ctx.data().config, &mut launches);
+
+    launches.retain(|flight| flight.flies_rocket(&rocket));
+
+    if launches.is_empty() {
+        ctx.say(format!("No upcoming launches found for {}.", rocket.trim())).await?;
+        return Ok(());
+    }
+
+    let embed_pages = launches
+        .iter()
+        .enumerate()
+        .map(|(i, flight)| flight.to_embed(i + 1, &ctx.data().config))
+        .collect();
+
+    paginate(ctx, embed_pages).await
+}
+
+/// Show a rocket's upcoming launches with a few stats
+#[poise::command(slash_command, check = "in_allowed_channel")]
+pub async fn rocket(
+    ctx: Context<'_>,
+    #[description = "Rocket name, e.g. Falcon 9"] name: String,
+) -> Result<(), Error> {
+    let scraped = scraped_launches(ctx).await?;
+    let query = name.trim().to_lowercase();
+    let matches = |flight: &FlightData| !flight.rocket.is_empty() && flight.rocket.to_lowercase().contains(&query);
+
+    let now = Utc::now();
+    let flown = scraped
+        .iter()
+        .filter(|flight| matches(flight) && !flight.approximate && flight.time < now && !flight.is_live(now))
+        .count();
+    let mut launches = scraped;
+    default_view(&ctx.data().config, &mut launches);
+    launches.retain(|flight| matches(flight));
+
+    if launches.is_empty() {
+        ctx.say(format!(
+            "No upcoming launches found for {}{}.",
+            name.trim(),
+            if flown > 0 { format!(" ({} already flown in the current listing)", flown) } else { String::new() }
+        )).await?;
+        return Ok(());
+    }
+
+    let next = launches
+        .iter()
+        .filter(|flight| !flight.approximate && flight.time >= now)
+        .min_by_key(|flight| flight.time)
+        .map_or_else(|| String::from("no firm date yet"), |flight| format!("next in {}", format_countdown(flight.time - now)));
+    let stats = format!(
+        "**{}**: {} upcoming, {}, {} already flown in the current listing.",
+        name.trim(),
+        launches.len(),
+        next,
+        flown
+    );
+
+    let embed_pages = launches
+        .iter()
+        .enumerate()
+        .map(|(i, flight)| flight.to_embed(i + 1, &ctx.data().config))
+        .collect();
+
+    paginate_from(ctx, embed_pages, PagerOptions { content: Some(stats), ..Default::default() }, |_| {}).await
+}
+
+/// Show where and when the launch list was fetched
+#[poise::command(slash_command)]
+pub async fn provenance(ctx: Context<'_>) -> Result<(), Error> {
+    let Some(provenance) = ctx.data().launches.provenance() else {
+        ctx.say("Nothing has been fetched yet.").await?;
+        return Ok(());
+    };
+
+    let embed = CreateEmbed::new()
+        .title("Cached launch data")
+        .fields(vec![
+            ("Source", provenance.source.to_string(), true),
+            ("Fetched", format!("<t:{}:R>", provenance.fetched_on.timestamp()), true),
+            ("Launches", provenance.launch_count.to_string(), true),
+            ("Status", String::from(if provenance.fresh { "Fresh" } else { "Stale, refreshes on next use" }), true),
+        ])
+        .color(ctx.data().config.embed_colour);
+
+    ctx.send(CreateReply::default().embed(embed)).await?;
+
+    Ok(())
+}
+
+/// Send feedback to the bot owners
+#[poise::command(slash_command, user_cooldown = 300)]
+pub async fn feedback(
+    ctx: Context<'_>,
+    #[description = "What went wrong, or what you'd like to see"] message: String,
+) -> Result<(), Error> {
+    let origin = match ctx.guild() {
+        Some(guild) => guild.name.clone(),
+        None => String::from("Direct message"),
+    };
+
+    let embed = CreateEmbed::new()
+        .title("Feedback")
+        .description(truncate(&message, EMBED_DESCRIPTION_LIMIT))
+        .fields(vec![
+            ("From", format!("{} ({})", ctx.author().tag(), ctx.author().id), true),
+            ("Server", origin, true),
+        ])
+        .color(ctx.data().config.embed_colour);
+
+    let delivered = match ctx.data().config.feedback_channel {
+        Some(channel) => channel
+            .send_message(ctx, CreateMessage::new().embed(embed))
+            .await
+            .is_ok(),
+        None => {
+            let mut delivered = false;
+            for owner in &ctx.framework().options().owners {
+                delivered |= owner
+                    .direct_message(ctx, CreateMessage::new().embed(embed.clone()))
+                    .await
+                    .is_ok();
+            }
+            delivered
+        }
+    };
+
+    let reply = if delivered {
+        String::from("Thanks, your feedback was sent to the bot owner.")
+    } else {
+        String::from("Sorry, I couldn't deliver your feedback right now. Please try again later.")
+    };
+
+    ctx.send(CreateReply::default().content(reply).ephemeral(true)).await?;
+
+    Ok(())
+}
+
+/// Show the next launch from each provider
+#[poise::command(slash_command, check = "in_allowed_channel")]
+pub async fn top(
+    ctx: Context<'_>,
+    #[description = "How many providers to show"]
+    #[min = 1]
+    #[max = 25]
+    count: Option<usize>,
+) -> Result<(), Error> {
+    let mut launches = scraped_launches(ctx).await?;
+    default_view(&ctx.data().config, &mut launches);
+
+    let now = Utc::now();
+    let groups = group_by_provider(&launches);
+    let mut board: Vec<(&str, &FlightData)> = groups
+        .iter()
+        .filter_map(|(provider, flights)| Some((*provider, soonest(flights, now)?)))
+        .collect();
+    board.sort_by_key(|(_, flight)| flight.time);
+
+    if board.is_empty() {
+        ctx.say("No upcoming launches found.").await?;
+        return Ok(());
+    }
+
+    let count = count.unwrap_or(TOP_PROVIDERS_DEFAULT).clamp(1, EMBED_FIELD_COUNT_LIMIT);
+    let embed = CreateEmbed::new()
+        .footer(CreateEmbedFooter::new("Via NextSpaceflight"))
+        .title("Next up per provider")
+        .fields(board.iter().take(count).map(|(provider, flight)| (
+            truncate(provider, EMBED_FIELD_NAME_LIMIT),
+            truncate(
+                &format!("{}\n<t:{}:R>", flight.name.trim(), flight.time.timestamp()),
+                EMBED_FIELD_VALUE_LIMIT,
+            ),
+            false,
+        )))
+        .color(ctx.data().config.embed_colour);
+
+    ctx.send(CreateReply::default().embed(embed)).await?;
+
+    Ok(())
+}
+
+/// List streams for launches in the next few hours
+#[poise::command(slash_command, check = "in_allowed_channel")]
+pub async fn stream(
+    ctx: Context<'_>,
+    #[description = "How many hours ahead to look"]
+    #[min = 1]
+    #[max = 48]
+    hours: Option<i64>,
+) -> Result<(), Error> {
+    require_scrape_cooldown(ctx)?;
+    ctx.defer().await?;
+    let mut launches = ctx.data().launches.launches().await?;
+    default_view(&ctx.data().config, &mut launches);
+
+    let now = Utc::now();
+    let window = TimeDelta::hours(hours.unwrap_or(STREAM_WINDOW_HOURS).clamp(1, 48));
+    launches.retain(|flight| flight.is_live(now) || (!flight.approximate && flight.time >= now && flight.time - now <= window));
+
+    if launches.is_empty() {
+        ctx.say(format!("No launches in the next {} hours.", window.num_hours())).await?;
+        return Ok(());
+    }
+
+    let data = ctx.data();
+    let permits = &Semaphore::new(DETAIL_FETCH_CONCURRENCY);
+    let streams = join_all(launches.iter().map(|flight| async move {
+        let _permit = permits.acquire().await.ok()?;
+        match data.details.details(data.source.as_ref(), flight).await {
+            Ok(details) => Some((flight, details.stream?)),
+            Err(why) => {
+                eprintln!("Couldn't load details for {}: {}", flight.name.trim(), why);
+                None
+            }
+        }
+    })).await;
+    let streams: Vec<(&FlightData, String)> = streams.into_iter().flatten().collect();
+
+    if streams.is_empty() {
+        ctx.say(format!("No webcasts found for launches in the next {} hours.", window.num_hours())).await?;
+        return Ok(());
+    }
+
+    let embed = CreateEmbed::new()
+        .footer(CreateEmbedFooter::new("Via NextSpaceflight"))
+        .title(format!("Webcasts in the next {} hours", window.num_hours()))
+        .fields(streams.iter().take(EMBED_FIELD_COUNT_LIMIT).map(|(flight, url)| (
+            truncate(flight.name.trim(), EMBED_FIELD_NAME_LIMIT),
+            truncate(&format!("<t:{}:R> · [Watch]({})", flight.time.timestamp(), url), EMBED_FIELD_VALUE_LIMIT),
+            false,
+        )))
+        .color(data.config.embed_colour);
+
+    let buttons = streams
+        .iter()
+        .take(LINK_BUTTON_LIMIT)
+        .map(|(flight, url)| CreateButton::new_link(url).label(truncate(flight.name.trim(), BUTTON_LABEL_LIMIT)))
+        .collect();
+
+    ctx.send(CreateReply::default().embed(embed).components(vec![CreateActionRow::Buttons(buttons)])).await?;
+
+    Ok(())
+}
+
+fn dashboard_embed(launches: &[FlightData], now: DateTime<Utc>, colour: Colour) -> CreateEmbed {
+    let upcoming = launches
+        .iter()
+        .filter(|flight| flight.is_live(now) || (!flight.approximate && flight.time >= now))
+        .take(DASHBOARD_COUNT)
+        .map(|flight| {
+            let countdown = if flight.is_live(now) {
+                String::from("🔴 LIVE")
+            } else {
+                format!("T-{}", format_countdown(flight.time - now))
+            };
+            (
+                truncate(flight.name.trim(), EMBED_FIELD_NAME_LIMIT),
+                truncate(&format!("{}\n{}", countdown, flight.formatted_time()), EMBED_FIELD_VALUE_LIMIT),
+                false,
+            )
+        });
+
+    CreateEmbed::new()
+        .footer(CreateEmbedFooter::new("Via NextSpaceflight"))
+        .title("Launch dashboard")
+        .fields(upcoming)
+        .timestamp(now)
+        .color(colour)
+}
+
+async fn dashboard_launches(ctx: Context<'_>) -> Result<Vec<FlightData>, Error> {
+    let mut launches = scraped_launches(ctx).await?;
+    default_view(&ctx.data().config, &mut launches);
+    Ok(launches)
+}
+
+/// Post a dashboard of upcoming launches that keeps itself updated
+#[poise::command(slash_command, check = "in_allowed_channel")]
+pub async fn dashboard(
+    ctx: Context<'_>,
+    #[description = "How many minutes to keep updating"]
+    #[min = 1]
+    #[max = 60]
+    minutes: Option<u64>,
+) -> Result<(), Error> {
+    let colour = ctx.data().config.embed_colour;
+    let mut launches = dashboard_launches(ctx).await?;
+    let duration = Duration::from_secs(60 * minutes.unwrap_or(DASHBOARD_MINUTES_DEFAULT).clamp(1, 60));
+    let deadline = Instant::now() + duration.min(INTERACTION_TIMEOUT);
+    let stop_button_id = button_id(ctx.id(), "stop");
+
+    let mut message = ctx.send(
+        CreateReply::default()
+            .embed(dashboard_embed(&launches, Utc::now(), colour))
+            .components(vec![CreateActionRow::Buttons(vec![
+                CreateButton::new(&stop_button_id).label("Stop").style(ButtonStyle::Danger)
+            ])])
+    ).await?.into_message().await?;
+
+    loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            break;
+        }
+
+        let press = ComponentInteractionCollector::new(ctx)
+            .author_id(ctx.author().id)
+            .filter({
+                let stop_button_id = stop_button_id.clone();
+                move |press| press.data.custom_id == stop_button_id
+            })
+            .timeout(DASHBOARD_REFRESH.min(remaining))
+            .await;
+        if let Some(press) = press {
+            let _ = press.create_response(ctx.serenity_context(), CreateInteractionResponse::Acknowledge).await;
+            break;
+        }
+
+        match dashboard_launches(ctx).await {
+            Ok(fresh) => launches = fresh,
+            Err(why) => eprintln!("Dashboard couldn't refresh launches, keeping the last list: {}", why),
+        }
+
+        match message.edit(ctx, EditMessage::new().embed(dashboard_embed(&launches, Utc::now(), colour))).await {
+            Err(why) if is_unknown_message(&why) => {
+                println!("Stopping dashboard, its message is gone: {}", why);
+                return Ok(());
+            }
+            Err(why) => eprintln!("Couldn't update the dashboard, trying again next refresh: {}", why),
+            Ok(()) => {}
+        }
+    }
+
+    let stopped = EditMessage::new()
+        .embed(dashboard_embed(&launches, Utc::now(), colour).title("Launch dashboard (stopped)"))
+        .components(vec![]);
+    if let Err(why) = message.edit(ctx, stopped).await {
+        eprintln!("Couldn't mark the dashboard as stopped: {}", why);
+    }
+
+    Ok(())
+}
+
+/// Show upcoming crewed launches
+#[poise::command(slash_command, check = "in_allowed_channel")]
+pub async fn crewed(ctx: Context<'_>) -> Result<(), Error> {
+    let mut launches = scraped_launches(ctx).await?;
+    default_view(&ctx.data().config, &mut launches);
+    launches.retain(|flight| flight.crewed);
+
+    if launches.is_empty() {
+        ctx.say("No upcoming crewed launches found.").await?;
+        return Ok(());
+    }
+
+    let embed_pages = launches
+        .iter()
+        .enumerate()
+        .map(|(i, flight)| flight.to_embed(i + 1, &ctx.data().config))
+        .collect();
+
+    paginate(ctx, embed_pages).await
+}
+
+/// Highlight the most interesting upcoming launches
+#[poise::command(slash_command, check = "in_allowed_channel")]
+pub async fn topmissions(
+    ctx: Context<'_>,
+    #[description = "How many launches to show"]
+    #[min = 1]
+    #[max = 10]
+    count: Option<usize>,
+) -> Result<(), Error> {
+    let mut launches = scraped_launches(ctx).await?;
+    default_view(&ctx.data().config, &mut launches);
+
+    let now = Utc::now();
+    let mut ranked: Vec<(u32, Vec<&str>, &FlightData)> = launches
+        .iter()
+        .filter(|flight| flight.time >= now || flight.is_live(now))
+        .map(|flight| {
+            let (score, reasons) = flight.interest(now);
+            (score, reasons, flight)
+        })
+        .filter(|(score, _, _)| *score > 0)
+        .collect();
+    ranked.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| (a.2.time, a.2.name.trim()).cmp(&(b.2.time, b.2.name.trim()))));
+
+    if ranked.is_empty() {
+        ctx.say("Nothing stands out among the upcoming launches right now.").await?;
+        return Ok(());
+    }
+
+    let count = count.unwrap_or(TOP_MISSIONS_DEFAULT).clamp(1, EMBED_FIELD_COUNT_LIMIT);
+    let embed = CreateEmbed::new()
+        .footer(CreateEmbedFooter::new("Ranked by a keyword heuristic • Via NextSpaceflight"))
+        .title("Don't miss these")
+        .fields(ranked.iter().take(count).map(|(_, reasons, flight)| (
+            truncate(flight.name.trim(), EMBED_FIELD_NAME_LIMIT),
+            truncate(&format!("{}\n{}", flight.formatted_time(), reasons.join(", ")), EMBED_FIELD_VALUE_LIMIT),
+            false,
+        )))
+        .color(ctx.data().config.embed_colour);
+
+    ctx.send(CreateReply::default().embed(embed)).await?;
+
+    Ok(())
+}
+
+/// Set reminders for several upcoming launches at once
+#[poise::command(slash_command, check = "in_allowed_channel")]
+pub async fn remindall(
+    ctx: Context<'_>,
+    #[description = "How many upcoming launches to be reminded about"]
+    #[min = 1]
+    #[max = 10]
+    count: Option<usize>,
+    #[description = "Minutes before each launch to remind you"]
+    #[min = 5]
+    #[max = 1440]
+    lead_minutes: Option<i64>,
+) -> Result<(), Error> {
+    let mut launches = scraped_launches(ctx).await?;
+    default_view(&ctx.data().config, &mut launches);
+
+    let now = Utc::now();
+    let count = count.unwrap_or(REMINDALL_DEFAULT).clamp(1, REMINDALL_MAX);
+    let lead = TimeDelta::minutes(lead_minutes.unwrap_or(REMINDER_LEAD_DEFAULT_MINUTES).clamp(5, 1440));
+    let user_id = ctx.author().id;
+    let settings = ctx.data().store.user(user_id);
+
+    let mut reminders = Vec::new();
+    let mut skipped = Vec::new();
+    for flight in launches.iter().filter(|flight| !flight.approximate && flight.time > now).take(count) {
+        if flight.time - lead <= now {
+            skipped.push(format!("{} (launches within {})", flight.name.trim(), format_countdown(lead)));
+        } else if settings.has_reminder(&flight.key()) {
+            skipped.push(format!("{} (already reminded)", flight.name.trim()));
+        } else {
+            reminders.push(Reminder {
+                launch: flight.key(),
+                name: flight.name.trim().to_string(),
+                launch_at: flight.time.timestamp(),
+                remind_at: (flight.time - lead).timestamp(),
+            });
+        }
+    }
+
+    if reminders.is_empty() && skipped.is_empty() {
+        ctx.say("No upcoming launches found.").await?;
+        return Ok(());
+    }
+
+    let mut reply = if reminders.is_empty() {
+        String::from("No new reminders set.")
+    } else {
+        let listing = reminders
+            .iter()
+            .map(|reminder| format!("- {} — <t:{}:f>", reminder.name, reminder.remind_at))
+            .collect::<Vec<_>>()
+            .join("\n");
+        format!("Set {} reminder(s), I'll DM you at:\n{}", reminders.len(), listing)
+    };
+    if !skipped.is_empty() {
+        reply.push_str(&format!("\nSkipped: {}", skipped.join(", ")));
+    }
+
+    ctx.data().store.update_user(user_id, |settings| settings.reminders.extend(reminders))?;
+    ctx.send(CreateReply::default().content(truncate(&reply, MESSAGE_CONTENT_LIMIT)).ephemeral(true)).await?;
+
+    Ok(())
+}
+
+/// Show launches within a time window
+#[poise::command(slash_command, check = "in_allowed_channel")]
+pub async fn launchesin(
+    ctx: Context<'_>,
+    #[description = "Time window, e.g. 24h, 7d or 2w"] window: String,
+) -> Result<(), Error> {
+    let Some(duration) = parse_duration(&window) else {
+        ctx.say(format!("I couldn't understand `{}`. Try something like `24h`, `7d` or `2w`.", window.trim())).await?;
+        return Ok(());
+    };
+
+    let mut launches = scraped_launches(ctx).await?;
+    default_view(&ctx.data().config, &mut launches);
+
+    let now = Utc::now();
+    launches.retain(|flight| !flight.approximate && flight.time >= now && flight.time - now <= duration);
+
+    if launches.is_empty() {
+        ctx.say(format!("No launches in the next {}.", window.trim())).await?;
+        return Ok(());
+    }
+
+    let embed_pages = launches
+        .iter()
+        .enumerate()
+        .map(|(i, flight)| flight.to_embed(i + 1, &ctx.data().config))
+        .collect();
+
+    paginate(ctx, embed_pages).await
+}
+
+/// Look up a launch by name
+#[poise::command(slash_command, check = "in_allowed_channel")]
+pub async fn launch(
+    ctx: Context<'_>,
+    #[description = "Part of the launch or mission name"] name: String,
+) -> Result<(), Error> {
+    let mut launches = scraped_launches(ctx).await?;
+    default_view(&ctx.data().config, &mut launches);
+
+    let query = name.trim().to_lowercase();
+    let mut matches: Vec<(u8, &FlightData)> = launches
+        .iter()
+        .filter_map(|flight| Some((match_rank(&flight.name, &query)?, flight)))
+        .collect();
+    matches.sort_by_key(|(rank, flight)| (*rank, flight.time));
+
+    let flight = match matches.as_slice() {
+        [] => {
+            ctx.say(format!("No launches match `{}`.", name.trim())).await?;
+            return Ok(());
+        }
+        [(_, flight)] => *flight,
+        _ => {
+            let menu_id = button_id(ctx.id(), "launch");
+            let options = matches
+                .iter()
+                .take(SELECT_MENU_LIMIT)
+                .enumerate()
+                .map(|(i, (_, flight))| {
+                    CreateSelectMenuOption::new(truncate(flight.name.trim(), SELECT_LABEL_LIMIT), i.to_string())
+                        .description(truncate(&format!("{} · {}", flight.provider_key(), format_dual(flight.time, None)), SELECT_LABEL_LIMIT))
+                })
+                .collect();
+            let menu = CreateSelectMenu::new(&menu_id, CreateSelectMenuKind::String { options })
+                .placeholder("Pick a launch");
+
+            let mut message = ctx.send(
+                CreateReply::default()
+                    .content(format!("{} launches match `{}`:", matches.len(), name.trim()))
+                    .components(vec![CreateActionRow::SelectMenu(menu)])
+            ).await?.into_message().await?;
+
+            let Some(choice) = ComponentInteractionCollector::new(ctx)
+                .author_id(ctx.author().id)
+                .filter(move |choice| choice.data.custom_id == menu_id)
+                .timeout(INTERACTION_TIMEOUT)
+                .await
+            else {
+                match message.edit(ctx, EditMessage::new().components(vec![])).await {
+                    Err(why) if !is_unknown_message(&why) => return Err(why.into()),
+                    _ => return Ok(()),
+                }
+            };
+
+            let selected = match &choice.data.kind {
+                serenity::ComponentInteractionDataKind::StringSelect { values } => {
+                    values.first().and_then(|value| value.parse::<usize>().ok())
+                }
+                _ => None,
+            };
+            let Some((_, flight)) = selected.and_then(|i| matches.get(i)) else {
+                return Ok(());
+            };
+
+            choice.create_response(ctx.serenity_context(), CreateInteractionResponse::Acknowledge).await?;
+            let edit = EditMessage::new()
+                .content("")
+                .embed(launch_details_embed(ctx, flight).await)
+                .components(vec![]);
+            match message.edit(ctx, edit).await {
+                Err(why) if is_unknown_message(&why) => println!("Not showing the picked launch, its message is gone: {}", why),
+                Err(why) => return Err(why.into()),
+                Ok(()) => {}
+            }
+            return Ok(());
+        }
+    };
+
+    ctx.defer().await?;
+    ctx.send(CreateReply::default().embed(launch_details_embed(ctx, flight).await)).await?;
+
+    Ok(())
+}
+
+fn diff_field(name: &str, lines: &[String]) -> (String, String, bool) {
+    let mut value = String::new();
+    for line in lines {
+        if value.chars().count() + line.chars().count() + 1 > EMBED_FIELD_VALUE_LIMIT {
+            value.push('…');
+            break;
+        }
+        value.push_str(line);
+        value.push('\n');
+    }
+    if value.is_empty() {
+        value.push_str("None");
+    }
+
+    (format!("{} ({})", name, lines.len()), value, false)
+}
+
+/// Show what changed since the previous fetch
+#[poise::command(slash_command, owners_only)]
+pub async fn diff(ctx: Context<'_>) -> Result<(), Error> {
+    let Some((previous, current)) = ctx.data().launches.snapshots() else {
+        ctx.say("There's no earlier fetch to compare against yet.").await?;
+        return Ok(());
+    };
+
+    let previous: BTreeMap<&str, &FlightData> = previous.iter().map(|flight| (flight.name.trim(), flight)).collect();
+    let current: BTreeMap<&str, &FlightData> = current.iter().map(|flight| (flight.name.trim(), flight)).collect();
+
+    let added: Vec<String> = current
+        .iter()
+        .filter(|(name, _)| !previous.contains_key(*name))
+        .map(|(name, flight)| format!("{} — {}", name, flight.formatted_time()))
+        .collect();
+    let removed: Vec<String> = previous
+        .keys()
+        .filter(|name| !current.contains_key(*name))
+        .map(|name| name.to_string())
+        .collect();
+    let slipped: Vec<String> = current
+        .iter()
+        .filter_map(|(name, flight)| {
+            let before = previous.get(name)?;
+            (before.time != flight.time).then(|| format!(
+                "{} — {} → {}",
+                name,
+                before.formatted_time(),
+                flight.formatted_time()
+            ))
+        })
+        .collect();
+
+    let embed = CreateEmbed::new()
+        .footer(CreateEmbedFooter::new("Via NextSpaceflight"))
+        .title("Changes since the previous fetch")
+        .fields([
+            diff_field("Added", &added),
+            diff_field("Removed", &removed),
+            diff_field("Time changed", &slipped),
+        ])
+        .color(ctx.data().config.embed_colour);
+
+    ctx.send(CreateReply::default().embed(embed)).await?;
+
+    Ok(())
+}
+
+fn countdown_clock(remaining: TimeDelta) -> String {
+    let seconds = remaining.num_seconds().max(0);
+    let (days, hours, minutes, seconds) = (seconds / 86400, seconds / 3600 % 24, seconds / 60 % 60, seconds % 60);
+    if days > 0 {
+        format!("T-{}d {:02}:{:02}:{:02}", days, hours, minutes, seconds)
+    } else {
+        format!("T-{:02}:{:02}:{:02}", hours, minutes, seconds)
+    }
+}
+
+fn countdown_embed(flight: &FlightData, now: DateTime<Utc>, colour: Colour) -> CreateEmbed {
+    let countdown = if flight.time <= now {
+        String::from("🔴 Launching now")
+    } else {
+        countdown_clock(flight.time - now)
+    };
+
+    flight.base_embed(colour).field("Countdown", countdown, false)
+}
+
+/// Post a live countdown to a launch
+#[poise::command(slash_command, check = "in_allowed_channel")]
+pub async fn countdown(
+    ctx: Context<'_>,
+    #[description = "Launch number as shown by /fetch"] index: usize,
+) -> Result<(), Error> {
+    let mut launches = scraped_launches(ctx).await?;
+    default_view(&ctx.data().config, &mut launches);
+
+    let Some(flight) = index.checked_sub(1).and_then(|i| launches.get(i)) else {
+        ctx.say(format!("There is no launch #{}. Pick a number between 1 and {}.", index, launches.len())).await?;
+        return Ok(());
+    };
+    if flight.approximate {
+        ctx.say(format!("{} has no exact launch time yet, so there's nothing to count down to.", flight.name.trim())).await?;
+        return Ok(());
+    }
+
+    let colour = ctx.data().config.embed_colour;
+    let mut message = ctx
+        .send(CreateReply::default().embed(countdown_embed(flight, Utc::now(), colour)))
+        .await?
+        .into_message()
+        .await?;
+
+    let deadline = Instant::now() + INTERACTION_TIMEOUT;
+    while flight.time > Utc::now() && Instant::now() < deadline {
+        let until_launch = (flight.time - Utc::now()).to_std().unwrap_or_default();
+        tokio::time::sleep(COUNTDOWN_REFRESH.min(until_launch)).await;
+
+        if let Err(why) = message.edit(ctx, EditMessage::new().embed(countdown_embed(flight, Utc::now(), colour))).await {
+            eprintln!("Stopping countdown, couldn't update its message: {}", why);
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+/// Get DMs when a launch moves or is about to lift off
+#[poise::command(slash_command, check = "in_allowed_channel")]
+pub async fn watch(
+    ctx: Context<'_>,
+    #[description = "Launch number as shown by /fetch"] index: usize,
+) -> Result<(), Error> {
+    let mut launches = scraped_launches(ctx).await?;
+    default_view(&ctx.data().config, &mut launches);
+
+    let Some(flight) = index.checked_sub(1).and_then(|i| launches.get(i)) else {
+        ctx.say(format!("There is no launch #{}. Pick a number between 1 and {}.", index, launches.len())).await?;
+        return Ok(());
+    };
+
+    let user_id = ctx.author().id;
+    let key = flight.key();
+    let name = flight.name.trim().to_string();
+    let watching = ctx.data().store.user(user_id).watches.iter().any(|watch| watch.launch == key);
+
+    let reply = if watching {
+        ctx.data().store.update_user(user_id, |settings| settings.watches.retain(|watch| watch.launch != key))?;
+        format!("You're no longer watching {}.", name)
+    } else {
+        ctx.data().store.update_user(user_id, |settings| settings.watches.push(Watch {
+            launch: key,
+            name: name.clone(),
+            launch_at: flight.time.timestamp(),
+            notified: false,
+        }))?;
+        format!("I'll DM you before {} launches and if it's rescheduled. Run this again to stop.", name)
+    };
+
+    ctx.send(CreateReply::default().content(reply).ephemeral(true)).await?;
+
+    Ok(())
+}
+
+/// Show scraper and notification stats
+#[poise::command(slash_command)]
+pub async fn stats(ctx: Context<'_>) -> Result<(), Error> {
+    let data = ctx.data();
+    let parsing = match data.source.parse_stats() {
+        Some(stats) => format!(
+            "{} of {} cards parsed ({:.0}% dropped)",
+            stats.parsed,
+            stats.cards,
+            stats.drop_rate() * 100.0
+        ),
+        None => String::from("No page scraped yet"),
+    };
+    let cached = match data.launches.provenance() {
+        Some(provenance) => format!("{} launches from {}", provenance.launch_count, provenance.source),
+        None => String::from("Nothing fetched yet"),
+    };
+
+    let embed = CreateEmbed::new()
+        .title("Scraper stats")
+        .fields(vec![
+            ("Source", data.source.name().to_string(), true),
+            ("Cache", cached, true),
+            ("Last scrape", parsing, false),
+            ("Presence", data.presence.lock().unwrap().clone().unwrap_or_else(|| String::from("Not set yet")), false),
+            ("Notifications", format!(
+                "{} sent, {} failed",
+                data.deliveries.sent.load(Ordering::Relaxed),
+                data.deliveries.failed.load(Ordering::Relaxed)
+            ), false),
+        ])
+        .color(data.config.embed_colour);
+
+    ctx.send(CreateReply::default().embed(embed)).await?;
+
+    Ok(())
+}
+
+fn check_line(name: &str, result: Result<String, String>) -> (String, bool) {
+    match result {
+        Ok(detail) => (format!("✅ **{}**: {}", name, detail), true),
+        Err(detail) => (format!("❌ **{}**: {}", name, detail), false),
+    }
+}
+
+/// Run a quick end-to-end check of scraping, the store and embeds
+#[poise::command(slash_command, owners_only)]
+pub async fn selftest(ctx: Context<'_>) -> Result<(), Error> {
+    ctx.defer_ephemeral().await?;
+    let data = ctx.data();
+
+    let scraped = data.source.fetch().await;
+    let scrape = match &scraped {
+        Ok(flights) => Ok(match data.source.parse_stats() {
+            Some(stats) => format!("{} launches parsed, {:.0}% of cards dropped", flights.len(), stats.drop_rate() * 100.0),
+            None => format!("{} launches parsed", flights.len()),
+        }),
+        Err(why) => Err(why.to_string()),
+    };
+    let store = data
+        .store
+        .flush()
+        .map(|_| format!("{} guilds and {} users saved", data.store.guilds().len(), data.store.users().len()))
+        .map_err(|why| why.to_string());
+    let cache = match data.launches.provenance() {
+        Some(provenance) if provenance.launch_count > 0 => Ok(format!(
+            "{} launches from {}, fetched <t:{}:R>",
+            provenance.launch_count,
+            provenance.source,
+            provenance.fetched_on.timestamp()
+        )),
+        Some(_) => Err(String::from("the cache is empty")),
+        None => Err(String::from("nothing has been cached yet")),
+    };
+    let render = match scraped.as_deref().map(|flights| flights.first()) {
+        Ok(Some(flight)) => serde_json::to_value(flight.to_embed(1, &data.config))
+            .map(|_| format!("built an embed for {}", flight.name.trim()))
+            .map_err(|why| why.to_string()),
+        Ok(None) => Err(String::from("no launch to render")),
+        Err(_) => Err(String::from("skipped, the scrape failed")),
+    };
+
+    let checks = [
+        check_line("Scrape", scrape),
+        check_line("Store", store),
+        check_line("Cache", cache),
+        check_line("Render", render),
+    ];
+    let passed = checks.iter().filter(|(_, passed)| *passed).count();
+    let lines = checks.iter().map(|(line, _)| line.as_str()).collect::<Vec<_>>();
+
+    let embed = CreateEmbed::new()
+        .title(format!("Self-test: {} of {} checks passed", passed, checks.len()))
+        .description(lines.join("\n"))
+        .color(if passed == checks.len() { Colour::DARK_GREEN } else { Colour::RED });
+
+    ctx.send(CreateReply::default().embed(embed).ephemeral(true)).await?;
+
+    Ok(())
+}
+
+/// Owner tools for inspecting the scraped data
+#[poise::command(slash_command, owners_only, subcommands("raw_html"), subcommand_required)]
+pub async fn raw(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Download the current listing page
+#[poise::command(slash_command, owners_only, rename = "html")]
+pub async fn raw_html(ctx: Context<'_>) -> Result<(), Error> {
+    ctx.defer_ephemeral().await?;
+
+    let mut html = fetch_listing_html(&ctx.data().http_client).await?;
+    let size = html.len();
+    if size > ATTACHMENT_LIMIT {
+        let mut end = ATTACHMENT_LIMIT;
+        while !html.is_char_boundary(end) {
+            end -= 1;
+        }
+        html.truncate(end);
+    }
+
+    let note = if size > ATTACHMENT_LIMIT {
+        format!("Listing page was {} bytes, truncated to {}.", size, html.len())
+    } else {
+        format!("Listing page, {} bytes.", size)
+    };
+    let file = CreateAttachment::bytes(html.into_bytes(), format!("nextspaceflight-{}.html", Utc::now().format("%Y%m%d-%H%M%S")));
+
+    ctx.send(CreateReply::default().content(note).attachment(file).ephemeral(true)).await?;
+
+    Ok(())
+}
+
+/// Set the timezone used to show times
+#[poise::command(slash_command, subcommands("timezone_set", "timezone_clear", "timezone_show"), subcommand_required)]
+pub async fn timezone(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Set your timezone
+#[poise::command(slash_command, rename = "set")]
+pub async fn timezone_set(
+    ctx: Context<'_>,
+    #[description = "Your timezone as a UTC offset, e.g. UTC-4 or UTC+5:30"] zone: String,
+) -> Result<(), Error> {
+    let Some(parsed) = Zone::parse(&zone) else {
+        ctx.send(CreateReply::default()
+            .content(unknown_zone(&zone))
+            .ephemeral(true)).await?;
+        return Ok(());
+    };
+
+    ctx.data().store.update_user(ctx.author().id, |settings| settings.timezone = Some(zone.trim().to_string()))?;
+    ctx.send(CreateReply::default().content(format!("Your timezone is now {}.", parsed.name)).ephemeral(true)).await?;
+
+    Ok(())
+}
+
+/// Go back to showing times in UTC
+#[poise::command(slash_command, rename = "clear")]
+pub async fn timezone_clear(ctx: Context<'_>) -> Result<(), Error> {
+    ctx.data().store.update_user(ctx.author().id, |settings| settings.timezone = None)?;
+    ctx.send(CreateReply::default().content("Cleared your timezone, times will be shown in UTC.").ephemeral(true)).await?;
+
+    Ok(())
+}
+
+/// Show your timezone
+#[poise::command(slash_command, rename = "show")]
+pub async fn timezone_show(ctx: Context<'_>) -> Result<(), Error> {
+    let reply = match user_zone(ctx) {
+        Some(zone) => format!("Your timezone is {}. It's {} there.", zone.name, format_dual(Utc::now(), Some(&zone))),
+        None => String::from("You haven't set a timezone, so times are shown in UTC. Use `/timezone set` to pick one."),
+    };
+    ctx.send(CreateReply::default().content(reply).ephemeral(true)).await?;
+
+    Ok(())
+}
+
+/// Hold back notifications during set hours
+#[poise::command(slash_command, subcommands("quiethours_set", "quiethours_clear", "quiethours_show"), subcommand_required)]
+pub async fn quiethours(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+fn describe_quiet_hours(quiet_hours: QuietHours, zone: Option<&Zone>) -> String {
+    format!(
+        "{} to {} {}",
+        format_clock(quiet_hours.start),
+        format_clock(quiet_hours.end),
+        zone.map_or("UTC", |zone| zone.name.as_str())
+    )
+}
+
+/// Set your quiet hours
+#[poise::command(slash_command, rename = "set")]
+pub async fn quiethours_set(
+    ctx: Context<'_>,
+    #[description = "When quiet hours start, e.g. 22:00"] start: String,
+    #[description = "When quiet hours end, e.g. 07:30"] end: String,
+) -> Result<(), Error> {
+    let (Some(start), Some(end)) = (parse_clock(&start), parse_clock(&end)) else {
+        ctx.send(CreateReply::default().content("Use 24-hour times like `22:00` and `07:30`.").ephemeral(true)).await?;
+        return Ok(());
+    };
+    if start == end {
+        ctx.send(CreateReply::default().content("Quiet hours need to start and end at different times.").ephemeral(true)).await?;
+        return Ok(());
+    }
+
+    let quiet_hours = QuietHours { start, end };
+    ctx.data().store.update_user(ctx.author().id, |settings| settings.quiet_hours = Some(quiet_hours))?;
+
+    let zone = user_zone(ctx);
+    let mut reply = format!(
+        "Quiet hours set to {}. Notifications are held back until they end.",
+        describe_quiet_hours(quiet_hours, zone.as_ref())
+    );
+    if zone.is_none() {
+        reply.push_str(" Use `/timezone set` if you'd rather use your local time.");
+    }
+    ctx.send(CreateReply::default().content(reply).ephemeral(true)).await?;
+
+    Ok(())
+}
+
+/// Clear your quiet hours
+#[poise::command(slash_command, rename = "clear")]
+pub async fn quiethours_clear(ctx: Context<'_>) -> Result<(), Error> {
+    ctx.data().store.update_user(ctx.author().id, |settings| settings.quiet_hours = None)?;
+    ctx.send(CreateReply::default().content("Cleared your quiet hours.").ephemeral(true)).await?;
+
+    Ok(())
+}
+
+/// Show your quiet hours
+#[poise::command(slash_command, rename = "show")]
+pub async fn quiethours_show(ctx: Context<'_>) -> Result<(), Error> {
+    let reply = match ctx.data().store.user(ctx.author().id).quiet_hours {
+        Some(quiet_hours) => format!("Your quiet hours are {}.", describe_quiet_hours(quiet_hours, user_zone(ctx).as_ref())),
+        None => String::from("You haven't set quiet hours. Use `/quiethours set` to add them."),
+    };
+    ctx.send(CreateReply::default().content(reply).ephemeral(true)).await?;
+
+    Ok(())
+}
+
+fn help_commands<'a>(commands: &'a [poise::Command<Data, Error>], found: &mut Vec<&'a poise::Command<Data, Error>>) {
+    for command in commands.iter().filter(|command| !command.hide_in_help) {
+        found.push(command);
+        help_commands(&command.subcommands, found);
+    }
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, a) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, b) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a != *b);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+fn usage_examples(command: &poise::Command<Data, Error>) -> Vec<String> {
+    let usage = |arguments: Vec<String>| {
+        std::iter::once(format!("/{}", command.qualified_name)).chain(arguments).collect::<Vec<_>>().join(" ")
+    };
+    let required: Vec<String> = command
+        .parameters
+        .iter()
+        .filter(|parameter| parameter.required)
+        .map(|parameter| format!("{}:<{}>", parameter.name, parameter.name))
+        .collect();
+
+    let mut examples = vec![usage(required.clone())];
+    if let Some(optional) = command.parameters.iter().find(|parameter| !parameter.required) {
+        let value = optional
+            .choices
+            .first()
+            .map_or_else(|| format!("<{}>", optional.name), |choice| choice.name.clone());
+        examples.push(usage(required.into_iter().chain([format!("{}:{}", optional.name, value)]).collect()));
+    }
+    examples
+}
+
+fn command_help_embed(command: &poise::Command<Data, Error>, colour: Colour) -> CreateEmbed {
+    let description = command
+        .help_text
+        .as_deref()
+        .or(command.description.as_deref())
+        .unwrap_or("No description.");
+    let parameters: Vec<String> = command
+        .parameters
+        .iter()
+        .map(|parameter| {
+            let mut line = format!(
+                "`{}` ({}) — {}",
+                parameter.name,
+                if parameter.required { "required" } else { "optional" },
+                parameter.description.as_deref().unwrap_or("No description.")
+            );
+            if !parameter.choices.is_empty() {
+                let choices = parameter.choices.iter().map(|choice| choice.name.as_str()).collect::<Vec<_>>();
+                line.push_str(&format!(" Choices: {}.", choices.join(", ")));
+            }
+            line
+        })
+        .collect();
+    let subcommands: Vec<String> = command
+        .subcommands
+        .iter()
+        .filter(|subcommand| !subcommand.hide_in_help)
+        .map(|subcommand| format!(
+            "`/{}` — {}",
+            subcommand.qualified_name,
+            subcommand.description.as_deref().unwrap_or("No description.")
+        ))
+        .collect();
+
+    let mut embed = CreateEmbed::new()
+        .title(truncate(&format!("/{}", command.qualified_name), EMBED_TITLE_LIMIT))
+        .description(truncate(description, EMBED_DESCRIPTION_LIMIT))
+        .color(colour);
+
+    if !parameters.is_empty() {
+        embed = embed.field("Parameters", truncate(&parameters.join("\n"), EMBED_FIELD_VALUE_LIMIT), false);
+    }
+    if subcommands.is_empty() {
+        let examples = usage_examples(command).iter().map(|example| format!("`{}`", example)).collect::<Vec<_>>();
+        embed = embed.field("Examples", truncate(&examples.join("\n"), EMBED_FIELD_VALUE_LIMIT), false);
+    } else {
+        embed = embed.field("Subcommands", truncate(&subcommands.join("\n"), EMBED_FIELD_VALUE_LIMIT), false);
+    }
+    if command.owners_only {
+        embed = embed.footer(CreateEmbedFooter::new("Only the bot owners can use this command."));
+    }
+
+    embed
+}
+
+/// List commands or show details for one
+#[poise::command(slash_command)]
+pub async fn help(
+    ctx: Context<'_>,
+    #[description = "Command to show details for, e.g. fetch or subscriptions list"] command: Option<String>,
+) -> Result<(), Error> {
+    let colour = ctx.data().config.embed_colour;
+    let registered = &ctx.framework().options().commands;
+    let mut commands = Vec::new();
+    help_commands(registered, &mut commands);
+
+    let Some(query) = command else {
+        let lines = registered
+            .iter()
+            .filter(|command| !command.hide_in_help)
+            .map(|command| format!(
+                "`/{}` — {}",
+                command.qualified_name,
+                command.description.as_deref().unwrap_or("No description.")
+            ))
+            .collect::<Vec<_>>();
+        let embed = CreateEmbed::new()
+            .title("Commands")
+            .description(truncate(&lines.join("\n"), EMBED_DESCRIPTION_LIMIT))
+            .footer(CreateEmbedFooter::new("Use /help <command> for details on one command."))
+            .color(colour);
+        ctx.send(CreateReply::default().embed(embed).ephemeral(true)).await?;
+        return Ok(());
+    };
+
+    let query = query.trim().trim_start_matches('/').split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase();
+    let reply = match commands.iter().find(|command| command.qualified_name.eq_ignore_ascii_case(&query)) {
+        Some(command) => CreateReply::default().embed(command_help_embed(command, colour)),
+        None => {
+            let closest = commands
+                .iter()
+                .min_by_key(|command| edit_distance(&command.qualified_name, &query))
+                .map(|command| format!(" Did you mean `/{}`?", command.qualified_name))
+                .unwrap_or_default();
+            CreateReply::default().content(format!("There's no command called `{}`.{}", query, closest))
+        }
+    };
+    ctx.send(reply.ephemeral(true)).await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use serde_json::{json, Value};
+    use super::*;
+
+    fn config() -> Config {
+        Config {
+            allowed_channels: Vec::new(),
+            data_source: crate::config::SourceKind::NextSpaceflight,
+            source_fallback: false,
+            store_path: String::from("spacebot.json"),
+            blocklist: Vec::new(),
+            next_count: 5,
+            max_results: 100,
+            proxy: None,
+            sharding: crate::config::Sharding::Single,
+            feedback_channel: None,
+            embed_colour: Colour::new(0x99AAB5),
+            refresh_interval: None,
+            prefix_commands: false,
+            horizon: None,
+            scrape_cooldown: Duration::ZERO,
+            embed_fields: DEFAULT_EMBED_FIELDS.to_vec(),
+        }
+    }
+
+    fn flight(name: &str, time: DateTime<Utc>) -> FlightData {
+        let launch_site = String::from("SLC-40, Cape Canaveral SFS, Florida, USA");
+        FlightData {
+            name: name.to_string(),
+            provider: String::from("SpaceX"),
+            rocket: String::from("Falcon 9"),
+            time,
+            location: LaunchSite::parse(&launch_site),
+            launch_site,
+            details: Some(String::from("https://nextspaceflight.com/launches/details/1")),
+            crewed: false,
+            approximate: false,
+        }
+    }
+
+    fn launch_time() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2099, 1, 1, 12, 0, 0).unwrap()
+    }
+
+    fn field_names(embed: &Value) -> Vec<&str> {
+        embed["fields"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|field| field["name"].as_str().unwrap())
+            .collect()
+    }
+
+    fn embed_length(embed: &Value) -> usize {
+        let length = |value: &Value| value.as_str().map_or(0, |text| text.chars().count());
+        length(&embed["title"])
+            + length(&embed["description"])
+            + length(&embed["footer"]["text"])
+            + length(&embed["author"]["name"])
+            + embed["fields"]
+                .as_array()
+                .map_or(0, |fields| fields.iter().map(|field| length(&field["name"]) + length(&field["value"])).sum())
+    }
+
+    fn names(launches: &[FlightData]) -> Vec<&str> {
+        launches.iter().map(|flight| flight.name.as_str()).collect()
+    }
+
+    #[test]
+    fn sort_launches_lists_approximate_launches_after_scheduled_ones() {
+        let now = launch_time();
+        let mut tbd = flight("TBD early", now + TimeDelta::days(1));
+        tbd.approximate = true;
+        let mut tbd_later = flight("TBD late", now + TimeDelta::days(30));
+        tbd_later.approximate = true;
+        let mut launches = vec![
+            tbd_later,
+            flight("Scheduled late", now + TimeDelta::days(10)),
+            tbd,
+            flight("Live", now - TimeDelta::minutes(2)),
+            flight("Scheduled early", now + TimeDelta::days(2)),
+        ];
+
+        sort_launches(&mut launches, false, now);
+        assert_eq!(names(&launches), ["Live", "Scheduled early", "Scheduled late", "TBD early", "TBD late"]);
+
+        sort_launches(&mut launches, true, now);
+        assert_eq!(names(&launches), ["Live", "Scheduled late", "Scheduled early", "TBD late", "TBD early"]);
+    }
+
+    #[test]
+    fn sort_launches_breaks_time_ties_by_name_then_provider() {
+        let now = launch_time() - TimeDelta::days(1);
+        let mut rocket_lab = flight("Rideshare", launch_time());
+        rocket_lab.provider = String::from("Rocket Lab");
+        let expected = ["Alpha", "Rideshare", "Rideshare", "Zeta"];
+
+        for mut launches in [
+            vec![flight("Zeta", launch_time()), rocket_lab.clone(), flight("Rideshare", launch_time()), flight("Alpha", launch_time())],
+            vec![flight("Rideshare", launch_time()), flight("Alpha", launch_time()), flight("Zeta", launch_time()), rocket_lab.clone()],
+        ] {
+            sort_launches(&mut launches, false, now);
+            assert_eq!(names(&launches), expected);
+            assert_eq!(launches[1].provider, "Rocket Lab");
+            assert_eq!(launches[2].provider, "SpaceX");
+        }
+    }
+
+    #[test]
+    fn buttons_only_match_their_own_session() {
+        assert_eq!(button_id(12, "next"), "12:next");
+        assert!("123:next".starts_with("12"), "the old prefix check let 12 claim 123's buttons");
+
+        assert!(is_own_button("12:next", 12));
+        assert!(is_own_button("12:previous", 12));
+        assert!(!is_own_button("123:next", 12));
+        assert!(!is_own_button("12next", 12));
+        assert!(!is_own_button("1:next", 12));
+    }
+
+    #[test]
+    fn timed_views_skip_approximate_launches() {
+        let now = launch_time();
+        let mut tbd = flight("TBD", now - TimeDelta::days(3));
+        tbd.approximate = true;
+        let mut tbd_upcoming = flight("TBD upcoming", now + TimeDelta::days(3));
+        tbd_upcoming.approximate = true;
+        let launches = [tbd, tbd_upcoming, flight("Flown", now - TimeDelta::days(10)), flight("Scheduled", now + TimeDelta::days(5))];
+
+        let last = last_launch_by_provider(&launches, now);
+        assert_eq!(last.get("SpaceX"), Some(&(now - TimeDelta::days(10))));
+
+        let dashboard = serde_json::to_value(dashboard_embed(&launches, now, Colour::RED)).unwrap();
+        assert_eq!(field_names(&dashboard), ["Scheduled"]);
+    }
+
+    #[test]
+    fn next_page_wraps_around_both_ends() {
+        assert_eq!(next_page(0, 3, Direction::Next), 1);
+        assert_eq!(next_page(2, 3, Direction::Next), 0);
+        assert_eq!(next_page(1, 3, Direction::Previous), 0);
+        assert_eq!(next_page(0, 3, Direction::Previous), 2);
+    }
+
+    #[test]
+    fn next_page_stays_put_with_a_single_page() {
+        assert_eq!(next_page(0, 1, Direction::Next), 0);
+        assert_eq!(next_page(0, 1, Direction::Previous), 0);
+    }
+
+    #[test]
+    fn truncate_keeps_short_text_and_marks_cut_text() {
+        assert_eq!(truncate("Starlink", 10), "Starlink");
+        assert_eq!(truncate("Starlink Group 6-1", 10), "Starlink …");
+        assert_eq!(truncate("🚀🚀🚀", 2), "🚀…");
+    }
+
+    #[test]
+    fn long_titles_are_cut_to_the_title_limit() {
+        let flight = flight(&"A".repeat(300), launch_time());
+        let details = LaunchDetails::default();
+
+        for embed in [flight.to_embed(1, &config()), flight.details_embed(&details, Colour::RED)] {
+            let title = serde_json::to_value(embed).unwrap()["title"].as_str().unwrap().to_string();
+            assert_eq!(title.chars().count(), EMBED_TITLE_LIMIT);
+            assert!(title.ends_with('…'));
+        }
+    }
+
+    #[test]
+    fn long_field_values_are_cut_to_the_field_limit() {
+        let mut flight = flight("Falcon 9 | Starlink", launch_time());
+        flight.launch_site = "B".repeat(2000);
+        let details = LaunchDetails { orbit: Some("C".repeat(1500)), ..Default::default() };
+        let embed = serde_json::to_value(flight.details_embed(&details, Colour::RED)).unwrap();
+
+        for field in embed["fields"].as_array().unwrap() {
+            assert!(field["value"].as_str().unwrap().chars().count() <= EMBED_FIELD_VALUE_LIMIT);
+        }
+        assert_eq!(embed["fields"][2]["value"].as_str().unwrap().chars().count(), EMBED_FIELD_VALUE_LIMIT);
+    }
+
+    #[test]
+    fn details_embed_stays_within_the_total_limit() {
+        let mut flight = flight(&"A".repeat(300), launch_time());
+        flight.launch_site = "B".repeat(2000);
+        let details = LaunchDetails {
+            description: Some("D".repeat(5000)),
+            orbit: Some("O".repeat(2000)),
+            provider: Some("P".repeat(2000)),
+            weather: Some("W".repeat(2000)),
+            stream: Some("S".repeat(2000)),
+            patch: None,
+        };
+        let embed = serde_json::to_value(flight.details_embed(&details, Colour::RED)).unwrap();
+
+        assert!(embed_length(&embed) <= EMBED_TOTAL_LIMIT);
+        assert_eq!(field_names(&embed), ["Time", "Provider", "Launch Site", "Orbit", "Weather", "Stream"]);
+        assert!(embed["description"].as_str().unwrap().chars().count() < EMBED_DESCRIPTION_LIMIT);
+    }
+
+    #[test]
+    fn to_embed_serializes_title_footer_and_colour() {
+        let embed = serde_json::to_value(flight("Falcon 9 | Starlink", launch_time()).to_embed(3, &config())).unwrap();
+
+        assert_eq!(embed["title"], "#3 | Falcon 9 | Starlink");
+        assert_eq!(embed["url"], "https://nextspaceflight.com/launches/details/1");
+        assert_eq!(embed["footer"]["text"], "Via NextSpaceflight");
+        assert_eq!(embed["color"], 0x99AAB5);
+        assert_eq!(embed["author"]["name"], "SpaceX");
+        assert!(embed["timestamp"].is_string());
+    }
+
+    #[test]
+    fn to_embed_serializes_default_fields_in_order() {
+        let embed = serde_json::to_value(flight("Falcon 9 | Starlink", launch_time()).to_embed(1, &config())).unwrap();
+
+        assert_eq!(field_names(&embed), ["Time", "Pad", "Facility", "Region", "Country", "📍 Map"]);
+        assert_eq!(
+            embed["fields"][0],
+            json!({ "name": "Time", "value": format!("<t:{}:F>", launch_time().timestamp()), "inline": false }),
+        );
+        assert_eq!(embed["fields"][4]["value"], "🇺🇸 USA");
+    }
+
+    #[test]
+    fn titled_embed_follows_the_configured_field_order() {
+        let fields = [EmbedField::Countdown, EmbedField::Provider, EmbedField::Time];
+        let embed = serde_json::to_value(flight("Falcon 9 | Starlink", launch_time()).titled_embed("Starlink", Colour::RED, &fields)).unwrap();
+
+        assert_eq!(embed["title"], "Starlink");
+        assert_eq!(embed["color"], 0xE74C3C);
+        assert_eq!(field_names(&embed), ["Countdown", "Provider", "Time"]);
+    }
+
+    #[test]
+    fn titled_embed_marks_crewed_and_approximate_launches() {
+        let mut flight = flight("Crew-12", launch_time());
+        flight.crewed = true;
+        flight.approximate = true;
+        let embed = serde_json::to_value(flight.titled_embed("Crew-12", Colour::RED, &DEFAULT_EMBED_FIELDS)).unwrap();
+
+        assert_eq!(embed["title"], "TBD | 🧑‍🚀 Crew-12");
+        assert!(embed["timestamp"].is_null());
+        assert_eq!(embed["fields"][0]["value"], format!("NET <t:{}:D> (time TBD)", launch_time().timestamp()));
+    }
+}
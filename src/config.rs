@@ -0,0 +1,282 @@
+use std::env;
+use std::time::Duration;
+use chrono::TimeDelta;
+use poise::serenity_prelude::{ChannelId, Colour};
+use crate::launches::FlightData;
+
+const DEFAULT_NEXT_COUNT: usize = 5;
+const DEFAULT_MAX_RESULTS: usize = 100;
+const DEFAULT_REFRESH_INTERVAL: Duration = Duration::from_secs(300);
+const DEFAULT_SCRAPE_COOLDOWN: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SourceKind {
+    NextSpaceflight,
+    LaunchLibrary,
+}
+
+impl SourceKind {
+    fn parse(value: &str) -> Option<SourceKind> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "nextspaceflight" => Some(SourceKind::NextSpaceflight),
+            "launchlibrary" | "ll2" => Some(SourceKind::LaunchLibrary),
+            _ => None,
+        }
+    }
+
+    pub fn other(self) -> SourceKind {
+        match self {
+            SourceKind::NextSpaceflight => SourceKind::LaunchLibrary,
+            SourceKind::LaunchLibrary => SourceKind::NextSpaceflight,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sharding {
+    Single,
+    Auto,
+    Fixed(u32),
+}
+
+impl Sharding {
+    fn parse(value: &str) -> Option<Sharding> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "" | "1" => Some(Sharding::Single),
+            "auto" => Some(Sharding::Auto),
+            count => count.parse().ok().filter(|count| *count > 0).map(Sharding::Fixed),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Theme {
+    Neutral,
+    Dark,
+    Light,
+}
+
+impl Theme {
+    fn parse(value: &str) -> Option<Theme> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "" | "neutral" => Some(Theme::Neutral),
+            "dark" => Some(Theme::Dark),
+            "light" => Some(Theme::Light),
+            _ => None,
+        }
+    }
+
+    fn colour(self) -> Colour {
+        match self {
+            Theme::Neutral => Colour::new(0x99AAB5),
+            Theme::Dark => Colour::new(0xFFFFFF),
+            Theme::Light => Colour::new(0x2C2F33),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EmbedField {
+    Time,
+    Site,
+    Map,
+    Provider,
+    Countdown,
+}
+
+pub const DEFAULT_EMBED_FIELDS: [EmbedField; 3] = [EmbedField::Time, EmbedField::Site, EmbedField::Map];
+
+impl EmbedField {
+    fn parse(value: &str) -> Option<EmbedField> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "time" => Some(EmbedField::Time),
+            "site" | "location" => Some(EmbedField::Site),
+            "map" => Some(EmbedField::Map),
+            "provider" => Some(EmbedField::Provider),
+            "countdown" => Some(EmbedField::Countdown),
+            _ => None,
+        }
+    }
+}
+
+fn parse_embed_fields(value: &str) -> Vec<EmbedField> {
+    let mut fields = Vec::new();
+    for name in parse_list(value) {
+        match EmbedField::parse(name) {
+            Some(field) if !fields.contains(&field) => fields.push(field),
+            Some(_) => {}
+            None => eprintln!("Ignoring unknown field in EMBED_FIELDS: {}", name),
+        }
+    }
+
+    if fields.is_empty() {
+        DEFAULT_EMBED_FIELDS.to_vec()
+    } else {
+        fields
+    }
+}
+
+pub struct Config {
+    pub allowed_channels: Vec<ChannelId>,
+    pub data_source: SourceKind,
+    pub source_fallback: bool,
+    pub store_path: String,
+    pub blocklist: Vec<String>,
+    pub next_count: usize,
+    pub max_results: usize,
+    pub proxy: Option<String>,
+    pub sharding: Sharding,
+    pub feedback_channel: Option<ChannelId>,
+    pub embed_colour: Colour,
+    pub refresh_interval: Option<Duration>,
+    pub prefix_commands: bool,
+    pub horizon: Option<TimeDelta>,
+    pub scrape_cooldown: Duration,
+    pub embed_fields: Vec<EmbedField>,
+}
+
+impl Config {
+    pub fn from_env() -> Config {
+        Config {
+            allowed_channels: parse_list(&env::var("ALLOWED_CHANNELS").unwrap_or_default())
+                .filter_map(|id| match id.parse::<u64>() {
+                    Ok(id) if id != 0 => Some(ChannelId::new(id)),
+                    _ => {
+                        eprintln!("Ignoring invalid channel ID in ALLOWED_CHANNELS: {}", id);
+                        None
+                    }
+                })
+                .collect(),
+            data_source: match env::var("DATA_SOURCE") {
+                Ok(value) => SourceKind::parse(&value).unwrap_or_else(|| {
+                    eprintln!("Unknown DATA_SOURCE {:?}, using NextSpaceflight", value);
+                    SourceKind::NextSpaceflight
+                }),
+                Err(_) => SourceKind::NextSpaceflight,
+            },
+            source_fallback: parse_flag(&env::var("DATA_SOURCE_FALLBACK").unwrap_or_default()),
+            store_path: env::var("STORE_PATH").unwrap_or_else(|_| String::from("spacebot.json")),
+            blocklist: parse_list(&env::var("BLOCKLIST").unwrap_or_default())
+                .map(str::to_lowercase)
+                .collect(),
+            next_count: env::var("NEXT_COUNT")
+                .ok()
+                .and_then(|count| count.trim().parse().ok())
+                .unwrap_or(DEFAULT_NEXT_COUNT),
+            max_results: match env::var("MAX_RESULTS") {
+                Ok(value) => value.trim().parse().ok().filter(|max| *max > 0).unwrap_or_else(|| {
+                    eprintln!("Invalid MAX_RESULTS {:?}, showing up to {} results", value, DEFAULT_MAX_RESULTS);
+                    DEFAULT_MAX_RESULTS
+                }),
+                Err(_) => DEFAULT_MAX_RESULTS,
+            },
+            proxy: env::var("PROXY_URL")
+                .or_else(|_| env::var("HTTPS_PROXY"))
+                .ok()
+                .filter(|url| !url.trim().is_empty()),
+            sharding: match env::var("SHARD_COUNT") {
+                Ok(value) => Sharding::parse(&value).unwrap_or_else(|| {
+                    eprintln!("Invalid SHARD_COUNT {:?}, running a single shard", value);
+                    Sharding::Single
+                }),
+                Err(_) => Sharding::Single,
+            },
+            feedback_channel: env::var("FEEDBACK_CHANNEL")
+                .ok()
+                .and_then(|id| id.trim().parse::<u64>().ok())
+                .filter(|id| *id != 0)
+                .map(ChannelId::new),
+            embed_colour: {
+                let theme = match env::var("EMBED_THEME") {
+                    Ok(value) => Theme::parse(&value).unwrap_or_else(|| {
+                        eprintln!("Unknown EMBED_THEME {:?}, using the neutral theme", value);
+                        Theme::Neutral
+                    }),
+                    Err(_) => Theme::Neutral,
+                };
+                match env::var("EMBED_COLOR") {
+                    Ok(value) => parse_colour(&value).unwrap_or_else(|| {
+                        eprintln!("Invalid EMBED_COLOR {:?}, using the {:?} theme colour", value, theme);
+                        theme.colour()
+                    }),
+                    Err(_) => theme.colour(),
+                }
+            },
+            refresh_interval: match env::var("REFRESH_INTERVAL") {
+                Ok(value) => match value.trim().parse::<u64>() {
+                    Ok(0) => None,
+                    Ok(seconds) => Some(Duration::from_secs(seconds)),
+                    Err(_) => {
+                        eprintln!("Invalid REFRESH_INTERVAL {:?}, refreshing every {}s", value, DEFAULT_REFRESH_INTERVAL.as_secs());
+                        Some(DEFAULT_REFRESH_INTERVAL)
+                    }
+                },
+                Err(_) => Some(DEFAULT_REFRESH_INTERVAL),
+            },
+            prefix_commands: env::var("PREFIX_COMMANDS").map_or(true, |value| parse_flag(&value)),
+            embed_fields: parse_embed_fields(&env::var("EMBED_FIELDS").unwrap_or_default()),
+            scrape_cooldown: match env::var("SCRAPE_COOLDOWN") {
+                Ok(value) => value.trim().parse().map(Duration::from_secs).unwrap_or_else(|_| {
+                    eprintln!("Invalid SCRAPE_COOLDOWN {:?}, using {}s", value, DEFAULT_SCRAPE_COOLDOWN.as_secs());
+                    DEFAULT_SCRAPE_COOLDOWN
+                }),
+                Err(_) => DEFAULT_SCRAPE_COOLDOWN,
+            },
+            horizon: env::var("LAUNCH_HORIZON")
+                .ok()
+                .filter(|value| !value.trim().is_empty())
+                .and_then(|value| {
+                    let horizon = parse_duration(&value);
+                    if horizon.is_none() {
+                        eprintln!("Invalid LAUNCH_HORIZON {:?}, showing launches at any distance", value);
+                    }
+                    horizon
+                }),
+        }
+    }
+}
+
+impl Config {
+    pub fn is_blocked(&self, flight: &FlightData) -> bool {
+        let name = flight.name.to_lowercase();
+        let provider = flight.provider.to_lowercase();
+
+        self.blocklist
+            .iter()
+            .any(|blocked| name.contains(blocked) || provider.contains(blocked))
+    }
+}
+
+pub fn parse_duration(value: &str) -> Option<TimeDelta> {
+    let value = value.trim().to_ascii_lowercase();
+    let split = value.find(|c: char| !c.is_ascii_digit())?;
+    let (amount, unit) = value.split_at(split);
+    let amount: i64 = amount.parse().ok().filter(|amount| *amount > 0)?;
+
+    match unit.trim() {
+        "m" | "min" | "mins" | "minutes" => TimeDelta::try_minutes(amount),
+        "h" | "hr" | "hrs" | "hours" => TimeDelta::try_hours(amount),
+        "d" | "day" | "days" => TimeDelta::try_days(amount),
+        "w" | "week" | "weeks" => TimeDelta::try_weeks(amount),
+        _ => None,
+    }
+}
+
+fn parse_flag(value: &str) -> bool {
+    matches!(value.trim().to_ascii_lowercase().as_str(), "1" | "true" | "yes" | "on")
+}
+
+fn parse_colour(value: &str) -> Option<Colour> {
+    let value = value.trim();
+    let hex = value
+        .strip_prefix("0x")
+        .or_else(|| value.strip_prefix("0X"))
+        .or_else(|| value.strip_prefix('#'))
+        .unwrap_or(value);
+
+    u32::from_str_radix(hex, 16).ok().filter(|colour| *colour <= 0xFFFFFF).map(Colour::new)
+}
+
+fn parse_list(value: &str) -> impl Iterator<Item = &str> {
+    value.split(',').map(str::trim).filter(|item| !item.is_empty())
+}
@@ -0,0 +1,222 @@
+use scraper::{ElementRef, Html, Selector};
+use crate::Error;
+use crate::sources::NEXTSPACEFLIGHT_ROOT;
+
+const STREAM_HOSTS: [&str; 5] = ["youtube.com", "youtu.be", "twitch.tv", "x.com", "twitter.com"];
+
+const PATCH_HINTS: [&str; 3] = ["patch", "insignia", "mission_logo"];
+
+const NAMED_ENTITIES: [(&str, char); 7] = [
+    ("amp", '&'),
+    ("lt", '<'),
+    ("gt", '>'),
+    ("quot", '"'),
+    ("apos", '\''),
+    ("nbsp", ' '),
+    ("ndash", '–'),
+];
+
+#[derive(Debug, Clone, Default)]
+pub struct LaunchDetails {
+    pub description: Option<String>,
+    pub orbit: Option<String>,
+    pub provider: Option<String>,
+    pub weather: Option<String>,
+    pub stream: Option<String>,
+    pub patch: Option<String>,
+}
+
+impl LaunchDetails {
+    pub fn weather_percentage(&self) -> Option<u8> {
+        let weather = self.weather.as_deref()?;
+        let (before, _) = weather.split_once('%')?;
+        let digits = before.trim_end().rsplit(|c: char| !c.is_ascii_digit()).next()?;
+
+        digits.parse().ok().filter(|percentage| *percentage <= 100)
+    }
+}
+
+pub async fn fetch_details(client: &reqwest::Client, url: &str) -> Result<LaunchDetails, Error> {
+    let res = client.get(url).send().await?.error_for_status()?.text().await?;
+    Ok(parse_details(&res))
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn decode_entity(entity: &str) -> Option<char> {
+    if let Some(hex) = entity.strip_prefix("#x").or_else(|| entity.strip_prefix("#X")) {
+        return char::from_u32(u32::from_str_radix(hex, 16).ok()?);
+    }
+    if let Some(decimal) = entity.strip_prefix('#') {
+        return char::from_u32(decimal.parse().ok()?);
+    }
+    NAMED_ENTITIES.iter().find(|(name, _)| *name == entity).map(|(_, decoded)| *decoded)
+}
+
+pub fn decode_entities(text: &str) -> String {
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let entity = rest[1..]
+            .find(';')
+            .filter(|end| *end <= 10)
+            .and_then(|end| Some((decode_entity(&rest[1..=end])?, end)));
+        match entity {
+            Some((character, end)) => {
+                decoded.push(character);
+                rest = &rest[end + 2..];
+            }
+            None => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+
+    decoded.push_str(rest);
+    decoded
+}
+
+fn labelled_value(document: &Html, labels: &[&str]) -> Option<String> {
+    document
+        .root_element()
+        .text()
+        .filter_map(|text| text.trim().split_once(':'))
+        .find(|(label, _)| labels.iter().any(|wanted| label.trim().eq_ignore_ascii_case(wanted)))
+        .map(|(_, value)| decode_entities(value.trim()))
+        .filter(|value| !value.is_empty())
+}
+
+fn mission_description(document: &Html) -> Option<String> {
+    let heading = Selector::parse("h3, h4").unwrap();
+    let supporting_text = Selector::parse(".mdl-card__supporting-text").unwrap();
+
+    document
+        .select(&heading)
+        .filter(|h| h.text().collect::<String>().trim().eq_ignore_ascii_case("Mission Details"))
+        .filter_map(|h| h.parent().and_then(ElementRef::wrap))
+        .filter_map(|card| card.select(&supporting_text).next())
+        .map(|text| collapse_whitespace(&decode_entities(&text.text().collect::<Vec<_>>().join(" "))))
+        .find(|text| !text.is_empty())
+}
+
+pub fn normalize_url(url: &str) -> Option<String> {
+    let url = url.trim();
+    if url.starts_with("https://") || url.starts_with("http://") {
+        Some(url.to_string())
+    } else if let Some(rest) = url.strip_prefix("//") {
+        Some(format!("https://{}", rest))
+    } else if url.starts_with('/') {
+        Some(format!("{}{}", NEXTSPACEFLIGHT_ROOT, url))
+    } else {
+        None
+    }
+}
+
+fn mission_patch(document: &Html) -> Option<String> {
+    let image = Selector::parse("img").unwrap();
+
+    document
+        .select(&image)
+        .filter(|img| {
+            let value = img.value();
+            [value.attr("alt"), value.attr("class"), value.attr("src")]
+                .into_iter()
+                .flatten()
+                .any(|text| PATCH_HINTS.iter().any(|hint| text.to_lowercase().contains(hint)))
+        })
+        .filter_map(|img| img.value().attr("src").or_else(|| img.value().attr("data-src")))
+        .find_map(normalize_url)
+}
+
+fn url_host(url: &str) -> Option<String> {
+    let url = url.trim();
+    let rest = url
+        .strip_prefix("https://")
+        .or_else(|| url.strip_prefix("http://"))
+        .or_else(|| url.strip_prefix("//"))?;
+    let authority = rest.split(['/', '?', '#']).next()?;
+    let host = authority.rsplit('@').next()?.split(':').next()?;
+
+    Some(host.to_ascii_lowercase()).filter(|host| !host.is_empty())
+}
+
+fn is_stream_url(url: &str) -> bool {
+    url_host(url).is_some_and(|host| {
+        STREAM_HOSTS
+            .iter()
+            .any(|stream_host| host == *stream_host || host.ends_with(&format!(".{}", stream_host)))
+    })
+}
+
+fn stream_link(document: &Html) -> Option<String> {
+    let link = Selector::parse("a[href]").unwrap();
+
+    document
+        .select(&link)
+        .filter_map(|a| a.value().attr("href"))
+        .find(|href| is_stream_url(href))
+        .and_then(normalize_url)
+}
+
+pub fn parse_details(html: &str) -> LaunchDetails {
+    let document = Html::parse_document(html);
+
+    LaunchDetails {
+        description: mission_description(&document),
+        orbit: labelled_value(&document, &["Orbit"]),
+        provider: labelled_value(&document, &["Organisation", "Organization", "Provider"]),
+        weather: labelled_value(&document, &["Weather", "Weather Forecast"]),
+        stream: stream_link(&document),
+        patch: mission_patch(&document),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_stream_hosts_exactly_or_as_subdomains() {
+        assert!(is_stream_url("https://www.youtube.com/watch?v=abc"));
+        assert!(is_stream_url("https://youtu.be/abc"));
+        assert!(is_stream_url("https://x.com/SpaceX/status/1"));
+        assert!(is_stream_url("//m.twitch.tv/nasa"));
+        assert!(!is_stream_url("https://www.spacex.com/launches/"));
+        assert!(!is_stream_url("https://example.com/?ref=youtube.com"));
+        assert!(!is_stream_url("/launches/details/1"));
+    }
+
+    #[test]
+    fn stream_link_adds_a_scheme_to_protocol_relative_hrefs() {
+        let details = parse_details(r#"<a href="/launches/details/1">Details</a><a href="//m.twitch.tv/nasa">Watch</a>"#);
+        assert_eq!(details.stream.as_deref(), Some("https://m.twitch.tv/nasa"));
+
+        let details = parse_details(r#"<a href=" https://www.youtube.com/watch?v=abc ">Watch</a>"#);
+        assert_eq!(details.stream.as_deref(), Some("https://www.youtube.com/watch?v=abc"));
+    }
+
+    #[test]
+    fn decodes_entities_left_in_detail_text_once() {
+        let details = parse_details(
+            r#"<div class="mdl-card"><h3>Mission Details</h3><div class="mdl-card__supporting-text">Deploys &amp;quot;Owl&amp;quot; &amp;amp; friends, not &amp;amp;amp;</div></div><p>Orbit: Low Earth Orbit &amp;amp; beyond</p>"#,
+        );
+
+        assert_eq!(details.description.as_deref(), Some(r#"Deploys "Owl" & friends, not &amp;"#));
+        assert_eq!(details.orbit.as_deref(), Some("Low Earth Orbit & beyond"));
+    }
+
+    #[test]
+    fn decode_entities_decodes_one_level() {
+        assert_eq!(decode_entities("Rocket Lab &amp; Friends"), "Rocket Lab & Friends");
+        assert_eq!(decode_entities("&#39;Beta&#39; &#x26; &quot;Gamma&quot;"), r#"'Beta' & "Gamma""#);
+        assert_eq!(decode_entities("&amp;amp;"), "&amp;");
+        assert_eq!(decode_entities("AT&T & &unknown; &"), "AT&T & &unknown; &");
+    }
+}
@@ -0,0 +1,41 @@
+use poise::serenity_prelude as serenity;
+
+#[derive(Debug, thiserror::Error)]
+pub enum SplinkError {
+    #[error("Couldn't reach the launch data source: {0}")]
+    Network(#[from] reqwest::Error),
+    #[error("The launch listing page layout seems to have changed.")]
+    LayoutChanged,
+    #[error("Couldn't parse data: {0}")]
+    Parse(String),
+    #[error("Discord error: {0}")]
+    Discord(Box<serenity::Error>),
+    #[error("Storage error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("{0}")]
+    Message(String),
+}
+
+impl From<serenity::Error> for SplinkError {
+    fn from(why: serenity::Error) -> SplinkError {
+        SplinkError::Discord(Box::new(why))
+    }
+}
+
+impl From<serde_json::Error> for SplinkError {
+    fn from(why: serde_json::Error) -> SplinkError {
+        SplinkError::Parse(why.to_string())
+    }
+}
+
+impl From<String> for SplinkError {
+    fn from(message: String) -> SplinkError {
+        SplinkError::Message(message)
+    }
+}
+
+impl From<&str> for SplinkError {
+    fn from(message: &str) -> SplinkError {
+        SplinkError::Message(message.to_string())
+    }
+}
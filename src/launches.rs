@@ -0,0 +1,334 @@
+use chrono::{DateTime, TimeDelta, Utc};
+
+pub const LIVE_BEFORE: TimeDelta = TimeDelta::minutes(5);
+pub const LIVE_AFTER: TimeDelta = TimeDelta::minutes(15);
+
+const SITE_COORDINATES: [(&str, f64, f64); 19] = [
+    ("Kennedy Space Center", 28.5729, -80.6490),
+    ("Cape Canaveral", 28.4889, -80.5778),
+    ("Vandenberg", 34.7420, -120.5724),
+    ("Starbase", 25.9972, -97.1560),
+    ("Wallops", 37.8402, -75.4881),
+    ("Kodiak", 57.4357, -152.3378),
+    ("Mahia", -39.2615, 177.8649),
+    ("Baikonur", 45.9650, 63.3050),
+    ("Plesetsk", 62.9257, 40.5776),
+    ("Vostochny", 51.8844, 128.3339),
+    ("Guiana", 5.2360, -52.7686),
+    ("Jiuquan", 40.9606, 100.2983),
+    ("Xichang", 28.2463, 102.0267),
+    ("Taiyuan", 38.8491, 111.6081),
+    ("Wenchang", 19.6145, 110.9510),
+    ("Satish Dhawan", 13.7199, 80.2304),
+    ("Tanegashima", 30.4009, 130.9750),
+    ("Uchinoura", 31.2510, 131.0813),
+    ("Naro", 34.4317, 127.5350),
+];
+
+const COUNTRY_FLAGS: [(&str, &str); 20] = [
+    ("USA", "🇺🇸"),
+    ("United States", "🇺🇸"),
+    ("China", "🇨🇳"),
+    ("People's Republic of China", "🇨🇳"),
+    ("Russia", "🇷🇺"),
+    ("Russian Federation", "🇷🇺"),
+    ("Kazakhstan", "🇰🇿"),
+    ("Republic of Kazakhstan", "🇰🇿"),
+    ("French Guiana", "🇬🇫"),
+    ("France", "🇫🇷"),
+    ("New Zealand", "🇳🇿"),
+    ("India", "🇮🇳"),
+    ("Japan", "🇯🇵"),
+    ("South Korea", "🇰🇷"),
+    ("Republic of Korea", "🇰🇷"),
+    ("North Korea", "🇰🇵"),
+    ("Iran", "🇮🇷"),
+    ("Israel", "🇮🇱"),
+    ("Australia", "🇦🇺"),
+    ("United Kingdom", "🇬🇧"),
+];
+
+const COUNTRY_ALIASES: [(&str, &str); 18] = [
+    ("US", "USA"),
+    ("United States", "USA"),
+    ("United States of America", "USA"),
+    ("America", "USA"),
+    ("PRC", "China"),
+    ("People's Republic of China", "China"),
+    ("Russian Federation", "Russia"),
+    ("Republic of Kazakhstan", "Kazakhstan"),
+    ("EU", "French Guiana"),
+    ("Europe", "French Guiana"),
+    ("France", "French Guiana"),
+    ("Kourou", "French Guiana"),
+    ("NZ", "New Zealand"),
+    ("Korea", "South Korea"),
+    ("Republic of Korea", "South Korea"),
+    ("UK", "United Kingdom"),
+    ("Britain", "United Kingdom"),
+    ("Great Britain", "United Kingdom"),
+];
+
+const ROCKET_PROVIDERS: [(&str, &str); 16] = [
+    ("Falcon", "SpaceX"),
+    ("Starship", "SpaceX"),
+    ("Electron", "Rocket Lab"),
+    ("Neutron", "Rocket Lab"),
+    ("Vulcan", "United Launch Alliance"),
+    ("Atlas V", "United Launch Alliance"),
+    ("New Glenn", "Blue Origin"),
+    ("New Shepard", "Blue Origin"),
+    ("Ariane", "Arianespace"),
+    ("Vega", "Arianespace"),
+    ("Antares", "Northrop Grumman"),
+    ("Minotaur", "Northrop Grumman"),
+    ("Alpha", "Firefly"),
+    ("Long March", "CASC"),
+    ("Ceres", "Galactic Energy"),
+    ("Zhuque", "LandSpace"),
+];
+
+const CREWED_KEYWORDS: [&str; 9] = [
+    "crew dragon",
+    "crew-",
+    "starliner",
+    "soyuz ms",
+    "shenzhou",
+    "axiom",
+    "ax-",
+    "polaris",
+    "artemis ii",
+];
+
+const INTEREST_KEYWORDS: [(&str, u32, &str); 12] = [
+    ("maiden", 40, "First flight"),
+    ("first flight", 40, "First flight"),
+    ("flight 1", 30, "First flight"),
+    ("mars", 30, "Interplanetary"),
+    ("jupiter", 30, "Interplanetary"),
+    ("europa", 30, "Interplanetary"),
+    ("asteroid", 25, "Interplanetary"),
+    ("artemis", 30, "Lunar"),
+    ("lunar", 25, "Lunar"),
+    ("moon", 25, "Lunar"),
+    ("starship", 20, "Notable"),
+    ("iss", 10, "Space station"),
+];
+const CREWED_INTEREST: u32 = 50;
+const IMMINENT_INTEREST: [(TimeDelta, u32); 2] = [(TimeDelta::hours(24), 20), (TimeDelta::hours(72), 10)];
+
+#[derive(Debug, Clone)]
+pub struct FlightData {
+    pub name: String,
+    pub provider: String,
+    pub rocket: String,
+    pub time: DateTime<Utc>,
+    pub launch_site: String,
+    pub location: LaunchSite,
+    pub details: Option<String>,
+    pub crewed: bool,
+    pub approximate: bool,
+}
+
+pub fn is_crewed(name: &str) -> bool {
+    let name = name.to_lowercase();
+    CREWED_KEYWORDS.iter().any(|keyword| {
+        name.match_indices(keyword).any(|(i, _)| {
+            let before = name[..i].chars().next_back();
+            let after = name[i + keyword.len()..].chars().next();
+            !before.is_some_and(char::is_alphanumeric)
+                && (keyword.ends_with('-') || !after.is_some_and(char::is_alphanumeric))
+        })
+    })
+}
+
+pub fn canonical_country(country: &str) -> String {
+    let country = country.trim();
+    COUNTRY_ALIASES
+        .iter()
+        .find(|(alias, _)| country.eq_ignore_ascii_case(alias))
+        .map_or(country, |(_, canonical)| *canonical)
+        .to_string()
+}
+
+pub fn provider_for_rocket(rocket: &str) -> Option<&'static str> {
+    let rocket = rocket.trim().to_lowercase();
+    ROCKET_PROVIDERS
+        .iter()
+        .find(|(family, _)| rocket.starts_with(&family.to_lowercase()))
+        .map(|(_, provider)| *provider)
+}
+
+pub fn rocket_from_name(name: &str) -> String {
+    match name.split_once('|') {
+        Some((rocket, _)) => rocket.trim().to_string(),
+        None => String::new(),
+    }
+}
+
+impl FlightData {
+    pub fn is_live(&self, now: DateTime<Utc>) -> bool {
+        !self.approximate && self.time - LIVE_BEFORE <= now && now <= self.time + LIVE_AFTER
+    }
+
+    pub fn flies_rocket(&self, query: &str) -> bool {
+        !self.rocket.is_empty() && self.rocket.to_lowercase().contains(&query.trim().to_lowercase())
+    }
+
+    pub fn interest(&self, now: DateTime<Utc>) -> (u32, Vec<&'static str>) {
+        let name = self.name.to_lowercase();
+        let has_word = |keyword: &str| {
+            name.match_indices(keyword).any(|(i, _)| {
+                !name[..i].chars().next_back().is_some_and(char::is_alphanumeric)
+                    && !name[i + keyword.len()..].chars().next().is_some_and(char::is_alphanumeric)
+            })
+        };
+
+        let mut score = 0;
+        let mut reasons = Vec::new();
+        if self.crewed {
+            score += CREWED_INTEREST;
+            reasons.push("Crewed");
+        }
+        for (keyword, weight, reason) in INTEREST_KEYWORDS {
+            if has_word(keyword) && !reasons.contains(&reason) {
+                score += weight;
+                reasons.push(reason);
+            }
+        }
+        if !self.approximate && self.time >= now {
+            if let Some((_, weight)) = IMMINENT_INTEREST.iter().find(|(within, _)| self.time - now <= *within) {
+                score += weight;
+                reasons.push("Soon");
+            }
+        }
+
+        (score, reasons)
+    }
+
+    pub fn key(&self) -> String {
+        match &self.details {
+            Some(url) => url.clone(),
+            None => format!("{}@{}", self.name.trim(), self.time.timestamp()),
+        }
+    }
+
+    pub fn coordinates(&self) -> Option<(f64, f64)> {
+        let site = self.launch_site.to_lowercase();
+        SITE_COORDINATES
+            .iter()
+            .find(|(name, _, _)| site.contains(&name.to_lowercase()))
+            .map(|(_, lat, lon)| (*lat, *lon))
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct LaunchSite {
+    pub pad: Option<String>,
+    pub facility: Option<String>,
+    pub region: Option<String>,
+    pub country: Option<String>,
+}
+
+impl LaunchSite {
+    pub fn flag(&self) -> Option<&'static str> {
+        let country = self.country.as_deref()?.trim();
+        COUNTRY_FLAGS
+            .iter()
+            .find(|(name, _)| country.eq_ignore_ascii_case(name))
+            .map(|(_, flag)| *flag)
+    }
+
+    pub fn canonical_country(&self) -> Option<String> {
+        self.country.as_deref().map(canonical_country)
+    }
+
+    pub fn parse(site: &str) -> LaunchSite {
+        let parts: Vec<&str> = site
+            .split(',')
+            .map(str::trim)
+            .filter(|part| !part.is_empty())
+            .collect();
+
+        let owned = |part: &str| Some(part.to_string());
+
+        match parts.as_slice() {
+            [] => LaunchSite::default(),
+            [facility] => LaunchSite { facility: owned(facility), ..Default::default() },
+            [facility, country] => LaunchSite {
+                facility: owned(facility),
+                country: owned(country),
+                ..Default::default()
+            },
+            [facility, region, country] => LaunchSite {
+                facility: owned(facility),
+                region: owned(region),
+                country: owned(country),
+                ..Default::default()
+            },
+            [pad, facility @ .., region, country] => LaunchSite {
+                pad: owned(pad),
+                facility: Some(facility.join(", ")),
+                region: owned(region),
+                country: owned(country),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn flight(name: &str) -> FlightData {
+        FlightData {
+            name: name.to_string(),
+            provider: String::from("SpaceX"),
+            rocket: rocket_from_name(name),
+            time: DateTime::UNIX_EPOCH,
+            launch_site: String::new(),
+            location: LaunchSite::default(),
+            details: None,
+            crewed: false,
+            approximate: false,
+        }
+    }
+
+    #[test]
+    fn rocket_from_name_takes_the_part_before_the_separator() {
+        assert_eq!(rocket_from_name("Falcon 9 | Starlink"), "Falcon 9");
+        assert_eq!(rocket_from_name("  Falcon 9 Block 5 |Starlink Group 6-1"), "Falcon 9 Block 5");
+        assert_eq!(rocket_from_name("Electron | Owl for One, Owl for All | Extra"), "Electron");
+    }
+
+    #[test]
+    fn rocket_from_name_is_empty_without_a_separator() {
+        assert_eq!(rocket_from_name("Starlink Group 6-1"), "");
+        assert_eq!(rocket_from_name(""), "");
+    }
+
+    #[test]
+    fn provider_for_rocket_normalizes_vehicle_variants() {
+        assert_eq!(provider_for_rocket("Falcon 9 Block 5"), Some("SpaceX"));
+        assert_eq!(provider_for_rocket("Falcon Heavy"), Some("SpaceX"));
+        assert_eq!(provider_for_rocket("  falcon 9  "), Some("SpaceX"));
+        assert_eq!(provider_for_rocket("Electron/Curie"), Some("Rocket Lab"));
+        assert_eq!(provider_for_rocket("LONG MARCH 2D"), Some("CASC"));
+        assert_eq!(provider_for_rocket(&rocket_from_name("Falcon 9 Block 5 | Starlink Group 6-12")), Some("SpaceX"));
+    }
+
+    #[test]
+    fn provider_for_rocket_is_none_for_unknown_vehicles() {
+        assert_eq!(provider_for_rocket("Soyuz 2.1b"), None);
+        assert_eq!(provider_for_rocket(""), None);
+    }
+
+    #[test]
+    fn flies_rocket_matches_case_insensitive_substrings() {
+        let falcon = flight("Falcon 9 Block 5 | Starlink");
+        assert!(falcon.flies_rocket("falcon 9"));
+        assert!(falcon.flies_rocket(" FALCON "));
+        assert!(!falcon.flies_rocket("Falcon Heavy"));
+        assert!(!flight("Starlink Group 6-1").flies_rocket("falcon"));
+    }
+}
@@ -1,41 +1,206 @@
 use std::env;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
 use poise::{Framework, FrameworkOptions, PrefixFrameworkOptions};
 use poise::builtins::register_globally;
-use poise::serenity_prelude::{ClientBuilder, GatewayIntents};
+use poise::serenity_prelude as serenity;
+use poise::serenity_prelude::{ClientBuilder, CreateAllowedMentions, GatewayIntents};
 
+mod announcements;
+mod cache;
 mod commands;
+mod config;
+mod details;
+mod error;
+mod launches;
+mod notifications;
+mod presence;
+mod refresher;
+mod sources;
+mod store;
+mod timezone;
 
-type Error = Box<dyn std::error::Error + Send + Sync>;
+type Error = error::SplinkError;
 type Context<'a> = poise::Context<'a, Data, Error>;
 
-pub struct Data {}
+const DEFAULT_PREFIX: &str = "!";
+
+pub struct Data {
+    config: config::Config,
+    source: Arc<dyn sources::DataSource>,
+    launches: Arc<cache::LaunchCache>,
+    details: cache::DetailCache,
+    store: Arc<store::Store>,
+    http_client: reqwest::Client,
+    deliveries: Arc<notifications::DeliveryStats>,
+    presence: Arc<Mutex<Option<String>>>,
+    scrape_cooldowns: Mutex<HashMap<serenity::UserId, Instant>>,
+}
+
+fn guild_prefix(ctx: poise::PartialContext<'_, Data, Error>) -> poise::BoxFuture<'_, Result<Option<String>, Error>> {
+    Box::pin(async move {
+        let prefix = ctx.guild_id.and_then(|guild_id| ctx.data.store.guild(guild_id).prefix);
+        Ok(Some(prefix.unwrap_or_else(|| DEFAULT_PREFIX.to_string())))
+    })
+}
 
 #[tokio::main]
 async fn main() {
+    let token = match env::var("DISCORD_TOKEN") {
+        Ok(token) if !token.trim().is_empty() => token,
+        _ => {
+            eprintln!("Error: DISCORD_TOKEN environment variable is not set.");
+            std::process::exit(1);
+        }
+    };
+    let config = config::Config::from_env();
+    let http_client = sources::build_client(config.proxy.as_deref()).unwrap_or_else(|why| {
+        eprintln!("Error: {}", why);
+        std::process::exit(1);
+    });
+    let store = Arc::new(store::Store::open(&config.store_path).unwrap_or_else(|why| {
+        eprintln!("Error: couldn't open the settings store at {}: {}", config.store_path, why);
+        std::process::exit(1);
+    }));
+
+    let sharding = config.sharding;
+    let intents = if config.prefix_commands {
+        GatewayIntents::non_privileged() | GatewayIntents::MESSAGE_CONTENT
+    } else {
+        eprintln!("Warning: PREFIX_COMMANDS is off, running with slash commands only.");
+        GatewayIntents::non_privileged()
+    };
+    let prefix_options = PrefixFrameworkOptions {
+        dynamic_prefix: config.prefix_commands.then_some(guild_prefix as _),
+        ..Default::default()
+    };
     let framework = Framework::builder()
         .options(FrameworkOptions {
-            commands: vec![commands::fetch()],
-            prefix_options: PrefixFrameworkOptions {
-                prefix: Some("!".into()),
-                ..Default::default()
-            },
+            commands: vec![
+                commands::fetch(),
+                commands::compareproviders(),
+                commands::ping(),
+                commands::launchdetails(),
+                commands::setchannel(),
+                commands::unsetchannel(),
+                commands::nextfor(),
+                commands::agencies(),
+                commands::summary(),
+                commands::next(),
+                commands::setnextcount(),
+                commands::setprefix(),
+                commands::setannouncerole(),
+                commands::coast(),
+                commands::country(),
+                commands::subscribe(),
+                commands::unsubscribe(),
+                commands::subscriptions(),
+                commands::watchlist(),
+                commands::byrocket(),
+                commands::rocket(),
+                commands::provenance(),
+                commands::feedback(),
+                commands::top(),
+                commands::stream(),
+                commands::dashboard(),
+                commands::crewed(),
+                commands::topmissions(),
+                commands::remindall(),
+                commands::launchesin(),
+                commands::launch(),
+                commands::diff(),
+                commands::countdown(),
+                commands::watch(),
+                commands::stats(),
+                commands::selftest(),
+                commands::raw(),
+                commands::timezone(),
+                commands::quiethours(),
+                commands::help(),
+                commands::register(),
+            ],
+            prefix_options,
+            allowed_mentions: Some(CreateAllowedMentions::new().replied_user(true)),
             ..Default::default()
         })
         .setup(move |ctx, _ready, framework| {
             Box::pin(async move {
                 println!("Logged in as {}", _ready.user.name);
-                register_globally(ctx, &framework.options().commands).await?;
-                Ok(Data {})
+                match register_globally(ctx, &framework.options().commands).await {
+                    Ok(()) => println!("Registered {} slash commands", framework.options().commands.len()),
+                    Err(why) => eprintln!(
+                        "Warning: slash command registration failed, continuing with the commands Discord already has: {}",
+                        why
+                    ),
+                }
+
+                let source = sources::from_config(&config, &http_client);
+                let launches = Arc::new(cache::LaunchCache::new(Arc::clone(&source)));
+                match config.refresh_interval {
+                    Some(interval) => refresher::spawn(Arc::clone(&launches), interval),
+                    None => {
+                        let warm_cache = Arc::clone(&launches);
+                        tokio::spawn(async move {
+                            match warm_cache.refresh().await {
+                                Ok(flights) => println!("Warmed launch cache with {} launches", flights.len()),
+                                Err(why) => eprintln!("Failed to warm launch cache: {}", why),
+                            }
+                        });
+                    }
+                }
+
+                announcements::spawn(ctx.http.clone(), Arc::clone(&launches), Arc::clone(&store), config.embed_colour);
+                let deliveries = Arc::new(notifications::DeliveryStats::default());
+                notifications::spawn(
+                    ctx.http.clone(),
+                    Arc::clone(&launches),
+                    Arc::clone(&store),
+                    config.embed_colour,
+                    Arc::clone(&deliveries),
+                );
+                let presence = Arc::new(Mutex::new(None));
+                presence::spawn(Arc::clone(framework.shard_manager()), Arc::clone(&launches), Arc::clone(&presence));
+
+                Ok(Data {
+                    config,
+                    source,
+                    launches,
+                    details: cache::DetailCache::default(),
+                    store,
+                    http_client,
+                    deliveries,
+                    presence,
+                    scrape_cooldowns: Mutex::default(),
+                })
             })
         })
         .build();
 
-    let token = env::var("DISCORD_TOKEN").expect("Expected a token in the environment");
-    let intents = GatewayIntents::non_privileged() | GatewayIntents::MESSAGE_CONTENT;
 
-    let client = ClientBuilder::new(token, intents)
+    let mut client = ClientBuilder::new(token, intents)
         .framework(framework)
-        .await;
+        .await
+        .unwrap();
+
+    let started = match sharding {
+        config::Sharding::Single => client.start().await,
+        config::Sharding::Auto => client.start_autosharded().await,
+        config::Sharding::Fixed(shards) => client.start_shards(shards).await,
+    };
 
-    client.unwrap().start().await.unwrap();
+    match started {
+        Ok(()) => {}
+        Err(serenity::Error::Gateway(serenity::GatewayError::DisallowedGatewayIntents)) => {
+            eprintln!(
+                "Error: Discord refused the Message Content intent. Enable it for the bot in the \
+                 developer portal, or set PREFIX_COMMANDS=false to run with slash commands only."
+            );
+            std::process::exit(1);
+        }
+        Err(why) => {
+            eprintln!("Error: the bot couldn't log in or lost its gateway connection: {}", why);
+            std::process::exit(1);
+        }
+    }
 }
\ No newline at end of file
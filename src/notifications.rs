@@ -0,0 +1,231 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+use chrono::{DateTime, TimeDelta, Utc};
+use poise::serenity_prelude::{Colour, CreateMessage, Http, UserId};
+use crate::cache::LaunchCache;
+use crate::commands::{format_countdown, is_rate_limited};
+use crate::launches::{FlightData, LIVE_AFTER};
+use crate::store::{Reminder, Store, Watch};
+
+const NOTIFY_INTERVAL: Duration = Duration::from_secs(60);
+pub const NOTIFY_LEAD: TimeDelta = TimeDelta::hours(1);
+const WATCH_SLIP: TimeDelta = TimeDelta::minutes(15);
+const DM_SPACING: Duration = Duration::from_millis(250);
+const DM_RATE_LIMIT_BACKOFF: Duration = Duration::from_secs(5);
+const DM_ATTEMPTS: u32 = 3;
+
+#[derive(Default)]
+pub struct DeliveryStats {
+    pub sent: AtomicUsize,
+    pub failed: AtomicUsize,
+}
+
+struct Dm {
+    user_id: UserId,
+    message: CreateMessage,
+    about: String,
+}
+
+async fn deliver(http: &Http, outbox: Vec<Dm>, stats: &DeliveryStats) {
+    for dm in outbox {
+        for attempt in 1..=DM_ATTEMPTS {
+            match dm.user_id.direct_message(http, dm.message.clone()).await {
+                Ok(_) => {
+                    stats.sent.fetch_add(1, Ordering::Relaxed);
+                    break;
+                }
+                Err(why) if is_rate_limited(&why) && attempt < DM_ATTEMPTS => {
+                    tokio::time::sleep(DM_RATE_LIMIT_BACKOFF * attempt).await;
+                }
+                Err(why) => {
+                    eprintln!("Failed to DM {} about {}: {}", dm.user_id, dm.about, why);
+                    stats.failed.fetch_add(1, Ordering::Relaxed);
+                    break;
+                }
+            }
+        }
+
+        tokio::time::sleep(DM_SPACING).await;
+    }
+}
+
+fn build_notification(flight: &FlightData, now: DateTime<Utc>, label: &str, colour: Colour) -> CreateMessage {
+    CreateMessage::new()
+        .content(format!(
+            "{}🚀 **{}** launches in {}.",
+            label,
+            flight.name.trim(),
+            format_countdown(flight.time - now)
+        ))
+        .embed(flight.base_embed(colour))
+}
+
+pub fn notification(flight: &FlightData, now: DateTime<Utc>, colour: Colour) -> CreateMessage {
+    build_notification(flight, now, "", colour)
+}
+
+pub fn test_notification(flight: &FlightData, now: DateTime<Utc>, colour: Colour) -> CreateMessage {
+    build_notification(flight, now, "(test notification) ", colour)
+}
+
+fn reminder(reminder: &Reminder, flight: Option<&FlightData>, now: DateTime<Utc>, colour: Colour) -> CreateMessage {
+    match flight {
+        Some(flight) => build_notification(flight, now, "⏰ Reminder: ", colour),
+        None => CreateMessage::new().content(format!(
+            "⏰ Reminder: **{}** was scheduled for <t:{}:F>.",
+            reminder.name.trim(),
+            reminder.launch_at
+        )),
+    }
+}
+
+fn update_watches(
+    watches: &[Watch],
+    flights: &[FlightData],
+    now: DateTime<Utc>,
+    colour: Colour,
+) -> (Vec<Watch>, Vec<CreateMessage>) {
+    let mut messages = Vec::new();
+    let mut updated = Vec::new();
+
+    for watch in watches {
+        let mut watch = watch.clone();
+        let flight = flights
+            .iter()
+            .find(|flight| flight.key() == watch.launch)
+            .or_else(|| flights.iter().find(|flight| flight.name.trim() == watch.name));
+
+        if let Some(flight) = flight {
+            let moved = flight.time.timestamp() - watch.launch_at;
+            if moved.abs() >= WATCH_SLIP.num_seconds() {
+                messages.push(CreateMessage::new().content(format!(
+                    "⏱️ **{}** moved from <t:{}:F> to {}.",
+                    watch.name,
+                    watch.launch_at,
+                    flight.formatted_time()
+                )));
+                if moved > 0 {
+                    watch.notified = false;
+                }
+            }
+            watch.launch = flight.key();
+            watch.launch_at = flight.time.timestamp();
+
+            if !watch.notified && !flight.approximate && flight.time >= now && flight.time - now <= NOTIFY_LEAD {
+                messages.push(notification(flight, now, colour));
+                watch.notified = true;
+            }
+        }
+
+        if watch.launch_at + LIVE_AFTER.num_seconds() > now.timestamp() {
+            updated.push(watch);
+        }
+    }
+
+    (updated, messages)
+}
+
+pub fn spawn(http: Arc<Http>, launches: Arc<LaunchCache>, store: Arc<Store>, colour: Colour, stats: Arc<DeliveryStats>) {
+    tokio::spawn(async move {
+        let mut notified = HashSet::new();
+        let mut interval = tokio::time::interval(NOTIFY_INTERVAL);
+
+        loop {
+            interval.tick().await;
+
+            let flights = match launches.launches().await {
+                Ok(flights) => flights,
+                Err(why) => {
+                    eprintln!("Skipping notifications, couldn't load launches: {}", why);
+                    continue;
+                }
+            };
+
+            let now = Utc::now();
+            let users = store.users();
+            let mut outbox = Vec::new();
+
+            for (user_id, settings) in &users {
+                if settings.is_quiet(now) {
+                    continue;
+                }
+
+                let due: Vec<&Reminder> = settings
+                    .reminders
+                    .iter()
+                    .filter(|reminder| reminder.remind_at <= now.timestamp())
+                    .collect();
+                if due.is_empty() {
+                    continue;
+                }
+
+                let cleared = store.update_user(*user_id, |settings| {
+                    settings.reminders.retain(|reminder| reminder.remind_at > now.timestamp())
+                });
+                if let Err(why) = cleared {
+                    eprintln!("Failed to clear delivered reminders for {}: {}", user_id, why);
+                    continue;
+                }
+
+                for due in due.into_iter().filter(|due| due.launch_at >= now.timestamp()) {
+                    let flight = flights.iter().find(|flight| flight.key() == due.launch);
+                    outbox.push(Dm {
+                        user_id: *user_id,
+                        message: reminder(due, flight, now, colour),
+                        about: format!("a reminder for {}", due.name.trim()),
+                    });
+                }
+            }
+
+            for (user_id, settings) in &users {
+                if settings.is_quiet(now) {
+                    continue;
+                }
+
+                let watches = store.user(*user_id).watches;
+                if watches.is_empty() {
+                    continue;
+                }
+
+                let (updated, messages) = update_watches(&watches, &flights, now, colour);
+                if updated != watches {
+                    if let Err(why) = store.update_user(*user_id, |settings| settings.watches = updated) {
+                        eprintln!("Failed to update watches for {}: {}", user_id, why);
+                        continue;
+                    }
+                }
+
+                outbox.extend(messages.into_iter().map(|message| Dm {
+                    user_id: *user_id,
+                    message,
+                    about: String::from("a watched launch"),
+                }));
+            }
+
+            for flight in flights {
+                if flight.approximate || flight.time < now || flight.time - now > NOTIFY_LEAD {
+                    continue;
+                }
+
+                for (user_id, settings) in &users {
+                    if !settings.is_subscribed(flight.provider_key()) || settings.is_quiet(now) {
+                        continue;
+                    }
+                    if !notified.insert((*user_id, flight.key())) {
+                        continue;
+                    }
+
+                    outbox.push(Dm {
+                        user_id: *user_id,
+                        message: notification(&flight, now, colour),
+                        about: flight.name.trim().to_string(),
+                    });
+                }
+            }
+
+            deliver(&http, outbox, &stats).await;
+        }
+    });
+}
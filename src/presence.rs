@@ -0,0 +1,60 @@
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use chrono::{DateTime, Utc};
+use poise::serenity_prelude::{ActivityData, ShardManager};
+use crate::cache::LaunchCache;
+use crate::launches::FlightData;
+
+const PRESENCE_INTERVAL: Duration = Duration::from_secs(60);
+const ACTIVITY_LIMIT: usize = 128;
+
+fn status(flights: &[FlightData], now: DateTime<Utc>) -> String {
+    let status = match flights.iter().find(|flight| flight.is_live(now)) {
+        Some(flight) => format!("🔴 LIVE: {}", flight.name.trim()),
+        None => match flights
+            .iter()
+            .filter(|flight| !flight.approximate && flight.time >= now)
+            .min_by_key(|flight| flight.time)
+        {
+            Some(flight) => format!("Next: {}", flight.name.trim()),
+            None => String::from("for launches"),
+        },
+    };
+
+    status.chars().take(ACTIVITY_LIMIT).collect()
+}
+
+pub fn spawn(shard_manager: Arc<ShardManager>, launches: Arc<LaunchCache>, last_status: Arc<Mutex<Option<String>>>) {
+    tokio::spawn(async move {
+        let mut applied = HashSet::new();
+        let mut interval = tokio::time::interval(PRESENCE_INTERVAL);
+
+        loop {
+            interval.tick().await;
+
+            let flights = match launches.launches().await {
+                Ok(flights) => flights,
+                Err(why) => {
+                    eprintln!("Skipping presence update, couldn't load launches: {}", why);
+                    continue;
+                }
+            };
+
+            let status = status(&flights, Utc::now());
+            {
+                let mut last_status = last_status.lock().unwrap();
+                if last_status.as_deref() != Some(status.as_str()) {
+                    *last_status = Some(status.clone());
+                    applied.clear();
+                }
+            }
+
+            for (shard_id, runner) in shard_manager.runners.lock().await.iter() {
+                if applied.insert(*shard_id) {
+                    runner.runner_tx.set_activity(Some(ActivityData::watching(&status)));
+                }
+            }
+        }
+    });
+}
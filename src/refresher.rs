@@ -0,0 +1,26 @@
+use std::sync::Arc;
+use std::time::Duration;
+use crate::cache::LaunchCache;
+
+const MAX_BACKOFF: Duration = Duration::from_secs(1800);
+
+pub fn spawn(launches: Arc<LaunchCache>, interval: Duration) {
+    tokio::spawn(async move {
+        let mut wait = interval;
+
+        loop {
+            match launches.refresh().await {
+                Ok(flights) => {
+                    println!("Refreshed launch cache with {} launches", flights.len());
+                    wait = interval;
+                }
+                Err(why) => {
+                    wait = (wait * 2).min(MAX_BACKOFF.max(interval));
+                    eprintln!("Failed to refresh launch cache, retrying in {}s: {}", wait.as_secs(), why);
+                }
+            }
+
+            tokio::time::sleep(wait).await;
+        }
+    });
+}
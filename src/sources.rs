@@ -0,0 +1,772 @@
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, NaiveDateTime, TimeDelta, Utc};
+use scraper::{ElementRef, Html, Selector};
+use serde::Deserialize;
+use crate::Error;
+use crate::error::SplinkError;
+use crate::config::{Config, SourceKind};
+use crate::details::{decode_entities, fetch_details, normalize_url, LaunchDetails};
+use crate::launches::{is_crewed, rocket_from_name, FlightData, LaunchSite};
+
+pub const NEXTSPACEFLIGHT_ROOT: &str = "https://nextspaceflight.com";
+const NEXTSPACEFLIGHT_LINK: &str = "https://nextspaceflight.com/launches/";
+pub const MAX_PAGES: usize = 5;
+const DROP_RATE_WARNING: f64 = 0.3;
+const MAX_LAUNCH_AGE: TimeDelta = TimeDelta::days(365);
+const MAX_LAUNCH_LEAD: TimeDelta = TimeDelta::days(5 * 365);
+const REQUIRE_LAUNCH_SITE: bool = false;
+const TIME_FORMATS: &[&str] = &[
+    "%a %B %d, %Y %H:%M",
+    "%a %B %d, %Y %H:%M:%S",
+    "%B %d, %Y %H:%M",
+    "%B %d, %Y %H:%M:%S",
+    "%a %B %d %Y %H:%M",
+    "%a, %B %d, %Y %H:%M",
+    "%Y-%m-%d %H:%M",
+    "%Y-%m-%d %H:%M:%S",
+];
+const LAUNCH_LIBRARY_ROOT: &str = "https://ll.thespacedevs.com";
+const LAUNCH_LIBRARY_LINK: &str = "https://ll.thespacedevs.com/2.2.0/launch/upcoming/";
+
+#[async_trait]
+pub trait DataSource: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    async fn fetch(&self) -> Result<Vec<FlightData>, Error>;
+
+    async fn fetch_tagged(&self) -> Result<(Vec<FlightData>, &'static str), Error> {
+        Ok((self.fetch().await?, self.name()))
+    }
+
+    async fn fetch_pages(&self, _pages: usize) -> Result<(Vec<FlightData>, usize), Error> {
+        Ok((self.fetch().await?, 1))
+    }
+
+    fn parse_stats(&self) -> Option<ParseStats> {
+        None
+    }
+
+    async fn details(&self, flight: &FlightData) -> Result<LaunchDetails, Error>;
+}
+
+fn owned_details_url<'a>(source: &dyn DataSource, flight: &'a FlightData, root: &str) -> Result<&'a str, Error> {
+    match flight.details.as_deref() {
+        Some(url) if url.starts_with(root) => Ok(url),
+        _ => Err(format!("{} has no details for {}", source.name(), flight.name.trim()).into()),
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ParseStats {
+    pub cards: usize,
+    pub parsed: usize,
+}
+
+impl ParseStats {
+    pub fn drop_rate(&self) -> f64 {
+        match self.cards {
+            0 => 0.0,
+            cards => (cards - self.parsed) as f64 / cards as f64,
+        }
+    }
+}
+
+pub struct NextSpaceflightSource {
+    client: reqwest::Client,
+    stats: Mutex<Option<ParseStats>>,
+}
+
+impl NextSpaceflightSource {
+    fn record(&self, stats: ParseStats) {
+        if stats.drop_rate() > DROP_RATE_WARNING {
+            eprintln!(
+                "Warning: dropped {} of {} NextSpaceflight cards, the page layout may have changed",
+                stats.cards - stats.parsed,
+                stats.cards
+            );
+        }
+        *self.stats.lock().unwrap() = Some(stats);
+    }
+}
+
+#[async_trait]
+impl DataSource for NextSpaceflightSource {
+    fn name(&self) -> &'static str {
+        "NextSpaceflight"
+    }
+
+    async fn fetch(&self) -> Result<Vec<FlightData>, Error> {
+        Ok(self.fetch_pages(1).await?.0)
+    }
+
+    async fn fetch_pages(&self, pages: usize) -> Result<(Vec<FlightData>, usize), Error> {
+        let mut launches = Vec::new();
+        let mut seen = HashSet::new();
+        let mut stats = ParseStats::default();
+        let mut next = Some(NEXTSPACEFLIGHT_LINK.to_string());
+        let mut scraped = 0;
+
+        while scraped < pages.clamp(1, MAX_PAGES) {
+            let Some(url) = next.take() else {
+                break;
+            };
+            let res = fetch_page_html(&self.client, &url).await?;
+            let (page, cards) = parse_launches(&res);
+            stats.cards += cards;
+            stats.parsed += page.len();
+            if page.is_empty() {
+                break;
+            }
+
+            launches.extend(page.into_iter().filter(|flight| seen.insert(flight.key())));
+            next = next_page_link(&res);
+            scraped += 1;
+        }
+
+        self.record(stats);
+        if launches.is_empty() {
+            return Err(SplinkError::LayoutChanged);
+        }
+
+        Ok((launches, scraped))
+    }
+
+    fn parse_stats(&self) -> Option<ParseStats> {
+        *self.stats.lock().unwrap()
+    }
+
+    async fn details(&self, flight: &FlightData) -> Result<LaunchDetails, Error> {
+        let url = owned_details_url(self, flight, NEXTSPACEFLIGHT_ROOT)?;
+        fetch_details(&self.client, url).await
+    }
+}
+
+fn launch_problem(flight: &FlightData, now: DateTime<Utc>) -> Option<&'static str> {
+    if flight.name.trim().is_empty() {
+        Some("empty name")
+    } else if flight.time < now - MAX_LAUNCH_AGE {
+        Some("time too far in the past")
+    } else if flight.time > now + MAX_LAUNCH_LEAD {
+        Some("time too far in the future")
+    } else if REQUIRE_LAUNCH_SITE && flight.launch_site.trim().is_empty() {
+        Some("no launch site")
+    } else {
+        None
+    }
+}
+
+fn is_valid_launch(flight: &FlightData) -> bool {
+    match launch_problem(flight, Utc::now()) {
+        Some(problem) => {
+            eprintln!("Dropping launch {:?} at {}: {}", flight.name.trim(), flight.time, problem);
+            false
+        }
+        None => true,
+    }
+}
+
+async fn fetch_page_html(client: &reqwest::Client, url: &str) -> Result<String, Error> {
+    Ok(client.get(url).send().await?.error_for_status()?.text().await?)
+}
+
+pub async fn fetch_listing_html(client: &reqwest::Client) -> Result<String, Error> {
+    fetch_page_html(client, NEXTSPACEFLIGHT_LINK).await
+}
+
+fn plausible(time: DateTime<Utc>, time_str: &str) -> Option<DateTime<Utc>> {
+    let now = Utc::now();
+    if (now - MAX_LAUNCH_AGE..=now + MAX_LAUNCH_LEAD).contains(&time) {
+        Some(time)
+    } else {
+        eprintln!("Ignoring implausible launch time {:?}", time_str);
+        None
+    }
+}
+
+fn parse_time(time_str: &str) -> Option<DateTime<Utc>> {
+    let time_str = time_str.trim().strip_suffix(" UTC")?.trim();
+    TIME_FORMATS
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(time_str, format).ok())
+        .map(|t| DateTime::from_naive_utc_and_offset(t, Utc))
+        .and_then(|time| plausible(time, time_str))
+}
+
+fn parse_approximate_time(time_str: &str) -> Option<DateTime<Utc>> {
+    let time_str = time_str.trim();
+    let time_str = time_str.strip_prefix("NET ").unwrap_or(time_str).trim();
+
+    NaiveDate::parse_from_str(time_str, "%a %B %d, %Y")
+        .or_else(|_| NaiveDate::parse_from_str(&format!("1 {}", time_str), "%d %B %Y"))
+        .ok()
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+        .map(|t| DateTime::from_naive_utc_and_offset(t, Utc))
+        .and_then(|time| plausible(time, time_str))
+}
+
+fn find_launch_site<'a>(candidates: &[&'a str]) -> Option<&'a str> {
+    candidates
+        .iter()
+        .find(|text| text.contains(','))
+        .or_else(|| candidates.last())
+        .copied()
+}
+
+fn details_link(card: &ElementRef, buttons: &Selector) -> Option<String> {
+    let href = card
+        .select(buttons)
+        .filter_map(|button| Some((button.value().attr("href")?, button.text().collect::<String>())))
+        .find(|(href, text)| href.contains("/launches/details/") || text.trim().eq_ignore_ascii_case("details"))
+        .map(|(href, _)| href)?;
+
+    Some(if href.starts_with("http") {
+        href.to_string()
+    } else {
+        format!("{}{}", NEXTSPACEFLIGHT_ROOT, href)
+    })
+}
+
+fn next_page_link(res: &str) -> Option<String> {
+    let document = Html::parse_document(res);
+    let links = Selector::parse("a[href], link[href]").unwrap();
+
+    document
+        .select(&links)
+        .filter(|link| {
+            let rel = link.value().attr("rel").unwrap_or_default();
+            let text = link.text().collect::<String>().trim().to_lowercase();
+            rel.split_whitespace().any(|rel| rel.eq_ignore_ascii_case("next"))
+                || matches!(text.as_str(), "next" | "next page" | "›" | "»")
+        })
+        .filter_map(|link| resolve_listing_link(link.value().attr("href")?))
+        .next()
+}
+
+fn resolve_listing_link(href: &str) -> Option<String> {
+    let href = href.trim();
+    let url = if href.starts_with('?') {
+        format!("{}{}", NEXTSPACEFLIGHT_LINK, href)
+    } else {
+        normalize_url(href)?
+    };
+
+    let on_site = url
+        .strip_prefix(NEXTSPACEFLIGHT_ROOT)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with(['/', '?']));
+    on_site.then_some(url)
+}
+
+fn parse_launches(res: &str) -> (Vec<FlightData>, usize) {
+    let document = Html::parse_document(res);
+
+    let mdl_card = Selector::parse(".mdl-card").unwrap();
+    let header = Selector::parse("h5.header-style").unwrap();
+    let organisation = Selector::parse(".mdl-card__title-text span").unwrap();
+    let launch_location = Selector::parse(".mdl-card__supporting-text").unwrap();
+    let details_button = Selector::parse(".mdc-button, a[href]").unwrap();
+
+    let cards: Vec<_> = document.select(&mdl_card).collect();
+    let launches = cards
+        .iter()
+        .filter_map(|launch| {
+            let launch_data: Vec<&str> = launch
+                .select(&launch_location)
+                .next()?
+                .text()
+                .map(str::trim)
+                .filter(|text| !text.is_empty())
+                .collect();
+
+            let (time_index, time, approximate) = launch_data
+                .iter()
+                .enumerate()
+                .find_map(|(i, text)| Some((i, parse_time(text)?, false)))
+                .or_else(|| {
+                    launch_data
+                        .iter()
+                        .enumerate()
+                        .find_map(|(i, text)| Some((i, parse_approximate_time(text)?, true)))
+                })?;
+            let launch_site = decode_entities(find_launch_site(&launch_data[time_index + 1..]).unwrap_or_default());
+            let name = decode_entities(launch.select(&header).next()?.text().next()?);
+
+            Some(FlightData {
+                rocket: rocket_from_name(&name),
+                crewed: is_crewed(&name),
+                approximate,
+                name,
+                provider: launch
+                    .select(&organisation)
+                    .next()
+                    .map(|org| decode_entities(org.text().collect::<String>().trim()))
+                    .unwrap_or_default(),
+                location: LaunchSite::parse(&launch_site),
+                launch_site,
+                time,
+                details: details_link(launch, &details_button),
+            })
+        })
+        .filter(is_valid_launch)
+        .collect();
+
+    (launches, cards.len())
+}
+
+
+pub struct LaunchLibrarySource {
+    client: reqwest::Client,
+}
+
+#[derive(Deserialize)]
+struct LaunchLibraryPage {
+    results: Vec<LaunchLibraryLaunch>,
+}
+
+#[derive(Deserialize)]
+struct LaunchLibraryLaunch {
+    name: String,
+    net: String,
+    url: String,
+    launch_service_provider: Option<LaunchLibraryNamed>,
+    pad: Option<LaunchLibraryPad>,
+    net_precision: Option<LaunchLibraryNamed>,
+}
+
+#[derive(Deserialize)]
+struct LaunchLibraryNamed {
+    name: String,
+}
+
+#[derive(Deserialize)]
+struct LaunchLibraryPad {
+    name: String,
+    location: Option<LaunchLibraryNamed>,
+}
+
+#[derive(Deserialize)]
+struct LaunchLibraryDetail {
+    mission: Option<LaunchLibraryMission>,
+    launch_service_provider: Option<LaunchLibraryNamed>,
+    probability: Option<i64>,
+    #[serde(rename = "vidURLs", default)]
+    vid_urls: Vec<LaunchLibraryVideo>,
+    #[serde(default)]
+    mission_patches: Vec<LaunchLibraryPatch>,
+}
+
+#[derive(Deserialize)]
+struct LaunchLibraryPatch {
+    image_url: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct LaunchLibraryMission {
+    description: Option<String>,
+    orbit: Option<LaunchLibraryNamed>,
+}
+
+#[derive(Deserialize)]
+struct LaunchLibraryVideo {
+    url: String,
+}
+
+impl LaunchLibraryDetail {
+    fn into_details(self) -> LaunchDetails {
+        let (description, orbit) = match self.mission {
+            Some(mission) => (mission.description, mission.orbit.map(|orbit| orbit.name)),
+            None => (None, None),
+        };
+
+        LaunchDetails {
+            description,
+            orbit,
+            provider: self.launch_service_provider.map(|provider| provider.name),
+            weather: self.probability
+                .filter(|probability| *probability >= 0)
+                .map(|probability| format!("{}% GO", probability)),
+            stream: self.vid_urls.into_iter().next().map(|video| video.url),
+            patch: self
+                .mission_patches
+                .into_iter()
+                .find_map(|patch| patch.image_url.as_deref().and_then(normalize_url)),
+        }
+    }
+}
+
+impl LaunchLibraryLaunch {
+    fn into_flight(self) -> Option<FlightData> {
+        let time = DateTime::parse_from_rfc3339(&self.net).ok()?.with_timezone(&Utc);
+        let launch_site = match self.pad {
+            Some(LaunchLibraryPad { name, location: Some(location) }) => format!("{}, {}", name, location.name),
+            Some(LaunchLibraryPad { name, location: None }) => name,
+            None => String::new(),
+        };
+
+        Some(FlightData {
+            rocket: rocket_from_name(&self.name),
+            crewed: is_crewed(&self.name),
+            approximate: self.net_precision.is_some_and(|precision| {
+                !matches!(precision.name.as_str(), "Second" | "Minute" | "Hour")
+            }),
+            name: self.name,
+            provider: self.launch_service_provider.map(|provider| provider.name).unwrap_or_default(),
+            location: LaunchSite::parse(&launch_site),
+            launch_site,
+            time,
+            details: Some(self.url),
+        })
+    }
+}
+
+#[async_trait]
+impl DataSource for LaunchLibrarySource {
+    fn name(&self) -> &'static str {
+        "Launch Library 2"
+    }
+
+    async fn fetch(&self) -> Result<Vec<FlightData>, Error> {
+        let res = self.client.get(LAUNCH_LIBRARY_LINK).send().await?.error_for_status()?.text().await?;
+        let page: LaunchLibraryPage = serde_json::from_str(&res)?;
+
+        Ok(page
+            .results
+            .into_iter()
+            .filter_map(LaunchLibraryLaunch::into_flight)
+            .filter(is_valid_launch)
+            .collect())
+    }
+
+    async fn details(&self, flight: &FlightData) -> Result<LaunchDetails, Error> {
+        let url = owned_details_url(self, flight, LAUNCH_LIBRARY_ROOT)?;
+
+        let res = self.client.get(url).send().await?.error_for_status()?.text().await?;
+        let detail: LaunchLibraryDetail = serde_json::from_str(&res)?;
+
+        Ok(detail.into_details())
+    }
+}
+
+pub struct FallbackSource {
+    primary: Box<dyn DataSource>,
+    secondary: Box<dyn DataSource>,
+}
+
+#[async_trait]
+impl DataSource for FallbackSource {
+    fn name(&self) -> &'static str {
+        self.primary.name()
+    }
+
+    async fn fetch(&self) -> Result<Vec<FlightData>, Error> {
+        Ok(self.fetch_tagged().await?.0)
+    }
+
+    async fn fetch_tagged(&self) -> Result<(Vec<FlightData>, &'static str), Error> {
+        match self.primary.fetch_tagged().await {
+            Ok(tagged) => Ok(tagged),
+            Err(why) => {
+                eprintln!(
+                    "{} failed ({}), falling back to {}",
+                    self.primary.name(),
+                    why,
+                    self.secondary.name()
+                );
+                self.secondary.fetch_tagged().await
+            }
+        }
+    }
+
+    async fn fetch_pages(&self, pages: usize) -> Result<(Vec<FlightData>, usize), Error> {
+        match self.primary.fetch_pages(pages).await {
+            Ok(fetched) => Ok(fetched),
+            Err(why) => {
+                eprintln!(
+                    "{} failed ({}), falling back to {}",
+                    self.primary.name(),
+                    why,
+                    self.secondary.name()
+                );
+                self.secondary.fetch_pages(pages).await
+            }
+        }
+    }
+
+    fn parse_stats(&self) -> Option<ParseStats> {
+        self.primary.parse_stats().or_else(|| self.secondary.parse_stats())
+    }
+
+    async fn details(&self, flight: &FlightData) -> Result<LaunchDetails, Error> {
+        match self.primary.details(flight).await {
+            Ok(details) => Ok(details),
+            Err(_) => self.secondary.details(flight).await,
+        }
+    }
+}
+
+pub fn build_client(proxy: Option<&str>) -> Result<reqwest::Client, Error> {
+    let builder = match proxy {
+        Some(url) => reqwest::Client::builder().proxy(
+            reqwest::Proxy::all(url).map_err(|why| format!("Invalid proxy URL {:?}: {}", url, why))?
+        ),
+        None => reqwest::Client::builder().no_proxy(),
+    };
+
+    Ok(builder.build()?)
+}
+
+fn source_for(kind: SourceKind, client: &reqwest::Client) -> Box<dyn DataSource> {
+    let client = client.clone();
+    match kind {
+        SourceKind::NextSpaceflight => Box::new(NextSpaceflightSource { client, stats: Mutex::default() }),
+        SourceKind::LaunchLibrary => Box::new(LaunchLibrarySource { client }),
+    }
+}
+
+pub fn from_config(config: &Config, client: &reqwest::Client) -> Arc<dyn DataSource> {
+    if !config.source_fallback {
+        return Arc::from(source_for(config.data_source, client));
+    }
+
+    Arc::new(FallbackSource {
+        primary: source_for(config.data_source, client),
+        secondary: source_for(config.data_source.other(), client),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Datelike, TimeZone};
+    use super::*;
+
+    fn next_year() -> i32 {
+        Utc::now().year() + 1
+    }
+
+    fn launch_time() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(next_year(), 3, 14, 13, 30, 0).unwrap()
+    }
+
+    fn card(name: &str, lines: &[&str], details: Option<&str>) -> String {
+        let button = details
+            .map(|href| format!(r#"<a class="mdc-button" href="{}">Details</a>"#, href))
+            .unwrap_or_default();
+        format!(
+            r#"<div class="mdl-card"><div class="mdl-card__title"><h5 class="header-style">{}</h5><div class="mdl-card__title-text"><span>SpaceX</span></div></div><div class="mdl-card__supporting-text">{}</div><div class="mdl-card__actions">{}</div></div>"#,
+            name,
+            lines.join("<br>"),
+            button,
+        )
+    }
+
+    fn time_line() -> String {
+        launch_time().format("%a %b %d, %Y %H:%M UTC").to_string()
+    }
+
+    fn parse_card(lines: &[&str]) -> FlightData {
+        let (mut launches, cards) = parse_launches(&card("Falcon 9 | Starlink", lines, Some("/launches/details/1")));
+        assert_eq!((launches.len(), cards), (1, 1));
+        launches.remove(0)
+    }
+
+    #[test]
+    fn parses_cards_with_three_four_and_five_lines() {
+        let time = time_line();
+        let fixtures: [(&[&str], &str); 3] = [
+            (&[&time, "LC-39A", "Kennedy Space Center, Florida, USA"], "Kennedy Space Center, Florida, USA"),
+            (&["Go for Launch", &time, "SLC-40", "Cape Canaveral SFS, Florida, USA"], "Cape Canaveral SFS, Florida, USA"),
+            (&["Go for Launch", "Webcast live", &time, "LC-1B", "Mahia Peninsula, New Zealand"], "Mahia Peninsula, New Zealand"),
+        ];
+
+        for (lines, site) in fixtures {
+            let flight = parse_card(lines);
+            assert_eq!(flight.time, launch_time());
+            assert_eq!(flight.launch_site, site);
+            assert_eq!(flight.name, "Falcon 9 | Starlink");
+            assert_eq!(flight.rocket, "Falcon 9");
+            assert_eq!(flight.provider, "SpaceX");
+        }
+    }
+
+    #[test]
+    fn falls_back_to_the_last_line_for_the_site() {
+        let flight = parse_card(&[&time_line(), "Vandenberg SFB"]);
+        assert_eq!(flight.launch_site, "Vandenberg SFB");
+    }
+
+    #[test]
+    fn skips_cards_without_a_time() {
+        let (launches, cards) = parse_launches(&card("Falcon 9 | Starlink", &["Go for Launch", "SLC-40"], None));
+        assert_eq!((launches.len(), cards), (0, 1));
+    }
+
+    #[test]
+    fn links_cards_with_a_details_button() {
+        let flight = parse_card(&[&time_line(), "Cape Canaveral SFS, Florida, USA"]);
+        assert_eq!(flight.details.as_deref(), Some("https://nextspaceflight.com/launches/details/1"));
+    }
+
+    #[test]
+    fn picks_the_details_button_over_a_watch_button() {
+        let watch = r#"<a class="mdc-button" href="https://www.youtube.com/watch?v=abc">Watch</a>"#;
+        let details = r#"<a class="mdc-button" href="/launches/details/7">Details</a>"#;
+        let time = time_line();
+
+        for buttons in [format!("{}{}", watch, details), format!("{}{}", details, watch)] {
+            let html = card("Falcon 9 | Starlink", &[&time, "Cape Canaveral SFS, Florida, USA"], None)
+                .replace(r#"<div class="mdl-card__actions"></div>"#, &format!(r#"<div class="mdl-card__actions">{}</div>"#, buttons));
+            let (launches, _) = parse_launches(&html);
+            assert_eq!(launches[0].details.as_deref(), Some("https://nextspaceflight.com/launches/details/7"));
+        }
+    }
+
+    #[test]
+    fn ignores_cards_with_only_unrelated_buttons() {
+        let time = time_line();
+        let html = card("Falcon 9 | Starlink", &[&time, "Cape Canaveral SFS, Florida, USA"], None).replace(
+            r#"<div class="mdl-card__actions"></div>"#,
+            r#"<div class="mdl-card__actions"><a class="mdc-button" href="https://www.youtube.com/watch?v=abc">Watch</a></div>"#,
+        );
+        let (launches, _) = parse_launches(&html);
+        assert_eq!(launches[0].details, None);
+    }
+
+    #[test]
+    fn keeps_cards_without_a_details_button() {
+        let time = time_line();
+        let (launches, cards) = parse_launches(&card("Electron | Owl Night Long", &[&time, "Mahia Peninsula, New Zealand"], None));
+        assert_eq!(cards, 1);
+        assert_eq!(launches.len(), 1);
+        assert_eq!(launches[0].details, None);
+        assert_eq!(launches[0].key(), format!("Electron | Owl Night Long@{}", launch_time().timestamp()));
+    }
+
+    #[test]
+    fn decodes_entities_left_in_card_text_once() {
+        let time = time_line();
+        let html = card("Electron | Rocket Lab &amp;amp; Friends", &[&time, "Launch Complex 1, Mahia &amp;amp; Co, New Zealand"], None)
+            .replace("<span>SpaceX</span>", "<span>Rocket Lab &amp;#39;Beta&amp;#39; &amp;amp;amp;</span>");
+        let (launches, _) = parse_launches(&html);
+
+        assert_eq!(launches[0].name, "Electron | Rocket Lab & Friends");
+        assert_eq!(launches[0].launch_site, "Launch Complex 1, Mahia & Co, New Zealand");
+        assert_eq!(launches[0].provider, "Rocket Lab 'Beta' &amp;");
+    }
+
+    #[test]
+    fn next_page_link_follows_the_next_link() {
+        let page = r#"<div class="pagination"><a href="https://nextspaceflight.com/launches/?page=1">1</a><a rel="next" href="https://nextspaceflight.com/launches/?page=2">2</a></div>"#;
+        assert_eq!(next_page_link(page).as_deref(), Some("https://nextspaceflight.com/launches/?page=2"));
+
+        let page = r#"<a class="mdc-button" href="https://nextspaceflight.com/launches/?page=3"> Next </a>"#;
+        assert_eq!(next_page_link(page).as_deref(), Some("https://nextspaceflight.com/launches/?page=3"));
+    }
+
+    #[test]
+    fn next_page_link_is_none_on_the_last_page() {
+        let page = r#"<div class="pagination"><a href="/launches/?page=1">Previous</a><a href="/launches/details/1">Details</a></div>"#;
+        assert_eq!(next_page_link(page), None);
+        assert_eq!(next_page_link(""), None);
+    }
+
+    #[test]
+    fn next_page_link_resolves_relative_hrefs() {
+        let page = r#"<a rel="next" href="/launches/?page=2">Next</a>"#;
+        assert_eq!(next_page_link(page).as_deref(), Some("https://nextspaceflight.com/launches/?page=2"));
+
+        let page = r#"<a rel="next" href="?page=2">Next</a>"#;
+        assert_eq!(next_page_link(page).as_deref(), Some("https://nextspaceflight.com/launches/?page=2"));
+    }
+
+    #[test]
+    fn next_page_link_skips_off_site_links() {
+        let page = r#"<a rel="next" href="https://ads.example.com/?page=2">Next</a><a href="//nextspaceflight.com.evil.example/launches/?page=2">»</a>"#;
+        assert_eq!(next_page_link(page), None);
+
+        let page = r#"<a href="https://ads.example.com/?page=2">Next</a><a rel="next" href="/launches/?page=2">2</a>"#;
+        assert_eq!(next_page_link(page).as_deref(), Some("https://nextspaceflight.com/launches/?page=2"));
+    }
+
+    #[test]
+    fn parse_time_reads_a_utc_time() {
+        let time_str = launch_time().format("%a %b %d, %Y %H:%M UTC").to_string();
+        assert_eq!(parse_time(&time_str), Some(launch_time()));
+    }
+
+    #[test]
+    fn parse_time_reads_every_known_format() {
+        for format in TIME_FORMATS {
+            let time_str = format!("{} UTC", launch_time().format(format));
+            assert_eq!(parse_time(&time_str), Some(launch_time()), "{} ({})", time_str, format);
+        }
+    }
+
+    #[test]
+    fn parse_time_reads_abbreviated_and_full_month_names() {
+        let year = next_year();
+        let weekday = launch_time().format("%a");
+        for month in ["Mar", "March"] {
+            assert_eq!(parse_time(&format!("{} {} 14, {} 13:30 UTC", weekday, month, year)), Some(launch_time()));
+            assert_eq!(parse_time(&format!("{} 14, {} 13:30:00 UTC", month, year)), Some(launch_time()));
+        }
+    }
+
+    #[test]
+    fn parse_time_rejects_absurd_years() {
+        for year in [1970, next_year() + 10, 9999] {
+            let time = Utc.with_ymd_and_hms(year, 1, 1, 0, 0, 0).unwrap();
+            assert_eq!(parse_time(&time.format("%a %b %d, %Y %H:%M UTC").to_string()), None);
+            assert_eq!(parse_approximate_time(&time.format("NET %B %Y").to_string()), None);
+        }
+    }
+
+    #[test]
+    fn launch_problem_uses_the_same_window() {
+        let now = Utc::now();
+        let mut flight = parse_card(&[&time_line(), "Cape Canaveral SFS, Florida, USA"]);
+        assert_eq!(launch_problem(&flight, now), None);
+
+        flight.time = now + MAX_LAUNCH_LEAD + TimeDelta::days(1);
+        assert_eq!(launch_problem(&flight, now), Some("time too far in the future"));
+        flight.time = now - MAX_LAUNCH_AGE - TimeDelta::days(1);
+        assert_eq!(launch_problem(&flight, now), Some("time too far in the past"));
+    }
+
+    #[test]
+    fn parse_time_rejects_other_zones() {
+        let time_str = launch_time().format("%a %b %d, %Y %H:%M").to_string();
+        assert_eq!(parse_time(&format!("{} PST", time_str)), None);
+        assert_eq!(parse_time(&format!("{} +0100", time_str)), None);
+        assert_eq!(parse_time(&time_str), None);
+    }
+
+    #[test]
+    fn parse_time_rejects_garbage() {
+        assert_eq!(parse_time(""), None);
+        assert_eq!(parse_time(" UTC"), None);
+        assert_eq!(parse_time("Launch time TBD"), None);
+        assert_eq!(parse_time(&launch_time().format("%a %b %d, %Y UTC").to_string()), None);
+        assert_eq!(parse_time(&launch_time().format("%a %b %d, %Y 25:61 UTC").to_string()), None);
+    }
+
+    #[test]
+    fn parse_time_leaves_approximate_forms_to_the_fallback() {
+        let year = next_year();
+        for time_str in [format!("NET Mar {}", year), format!("March {}", year)] {
+            assert_eq!(parse_time(&time_str), None);
+        }
+    }
+
+    #[test]
+    fn parse_approximate_time_reads_dates_and_months() {
+        let day = Utc.with_ymd_and_hms(next_year(), 3, 14, 0, 0, 0).unwrap();
+        let month = Utc.with_ymd_and_hms(next_year(), 3, 1, 0, 0, 0).unwrap();
+
+        assert_eq!(parse_approximate_time(&day.format("%a %b %d, %Y").to_string()), Some(day));
+        assert_eq!(parse_approximate_time(&day.format("NET %a %b %d, %Y").to_string()), Some(day));
+        assert_eq!(parse_approximate_time(&format!("NET Mar {}", next_year())), Some(month));
+        assert_eq!(parse_approximate_time(&format!("March {}", next_year())), Some(month));
+        assert_eq!(parse_approximate_time("NET TBD"), None);
+    }
+}
@@ -0,0 +1,276 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use poise::serenity_prelude::{ChannelId, GuildId, RoleId, UserId};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use crate::Error;
+use crate::timezone::{local_minute, Zone};
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GuildSettings {
+    pub announcement_channel: Option<ChannelId>,
+    #[serde(default)]
+    pub next_count: Option<usize>,
+    #[serde(default)]
+    pub prefix: Option<String>,
+    #[serde(default)]
+    pub announcement_role: Option<RoleId>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Reminder {
+    pub launch: String,
+    pub name: String,
+    pub launch_at: i64,
+    pub remind_at: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Watch {
+    pub launch: String,
+    pub name: String,
+    pub launch_at: i64,
+    #[serde(default)]
+    pub notified: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuietHours {
+    pub start: u32,
+    pub end: u32,
+}
+
+impl QuietHours {
+    pub fn contains(&self, minute: u32) -> bool {
+        if self.start <= self.end {
+            self.start <= minute && minute < self.end
+        } else {
+            minute >= self.start || minute < self.end
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UserSettings {
+    #[serde(default)]
+    pub subscriptions: Vec<String>,
+    #[serde(default)]
+    pub reminders: Vec<Reminder>,
+    #[serde(default)]
+    pub watches: Vec<Watch>,
+    #[serde(default)]
+    pub timezone: Option<String>,
+    #[serde(default)]
+    pub quiet_hours: Option<QuietHours>,
+}
+
+impl UserSettings {
+    pub fn is_subscribed(&self, provider: &str) -> bool {
+        self.subscriptions
+            .iter()
+            .any(|subscription| subscription.eq_ignore_ascii_case(provider))
+    }
+
+    pub fn is_quiet(&self, now: DateTime<Utc>) -> bool {
+        let Some(quiet_hours) = self.quiet_hours else {
+            return false;
+        };
+        let zone = self.timezone.as_deref().and_then(Zone::parse);
+        quiet_hours.contains(local_minute(now, zone.as_ref()))
+    }
+
+    pub fn has_reminder(&self, launch: &str) -> bool {
+        self.reminders.iter().any(|reminder| reminder.launch == launch)
+    }
+}
+
+type Migration = fn(&mut Map<String, Value>);
+
+const MIGRATIONS: [(&str, Migration); 1] = [
+    ("0001_fixed_offset_timezones", fixed_offset_timezones),
+];
+
+const LEGACY_ZONES: [(&str, &str); 12] = [
+    ("EST", "UTC-5"),
+    ("EDT", "UTC-4"),
+    ("CST", "UTC-6"),
+    ("CDT", "UTC-5"),
+    ("MST", "UTC-7"),
+    ("MDT", "UTC-6"),
+    ("PST", "UTC-8"),
+    ("PDT", "UTC-7"),
+    ("BST", "UTC+1"),
+    ("CET", "UTC+1"),
+    ("CEST", "UTC+2"),
+    ("AEST", "UTC+10"),
+];
+
+fn fixed_offset_timezones(data: &mut Map<String, Value>) {
+    let Some(users) = data.get_mut("users").and_then(Value::as_object_mut) else {
+        return;
+    };
+
+    for user in users.values_mut() {
+        let Some(timezone) = user.get_mut("timezone") else {
+            continue;
+        };
+        let legacy = timezone.as_str().and_then(|zone| {
+            LEGACY_ZONES.iter().find(|(name, _)| zone.trim().eq_ignore_ascii_case(name))
+        });
+        if let Some((_, offset)) = legacy {
+            *timezone = Value::from(*offset);
+        }
+    }
+}
+
+fn migrate(data: &mut Map<String, Value>) -> Vec<&'static str> {
+    let mut applied: Vec<String> = data
+        .get("schema_migrations")
+        .and_then(|applied| serde_json::from_value(applied.clone()).ok())
+        .unwrap_or_default();
+
+    let mut ran = Vec::new();
+    for (name, migration) in MIGRATIONS {
+        if applied.iter().any(|applied| applied == name) {
+            continue;
+        }
+        migration(data);
+        applied.push(name.to_string());
+        ran.push(name);
+    }
+
+    data.insert(String::from("schema_migrations"), Value::from(applied));
+    ran
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct StoreData {
+    #[serde(default)]
+    schema_migrations: Vec<String>,
+    #[serde(default)]
+    guilds: HashMap<GuildId, GuildSettings>,
+    #[serde(default)]
+    users: HashMap<UserId, UserSettings>,
+}
+
+pub struct Store {
+    path: PathBuf,
+    data: Mutex<StoreData>,
+}
+
+impl Store {
+    pub fn open(path: impl Into<PathBuf>) -> Result<Store, Error> {
+        let path = path.into();
+        let mut raw: Map<String, Value> = match fs::read_to_string(&path) {
+            Ok(contents) => serde_json::from_str(&contents)?,
+            Err(why) if why.kind() == ErrorKind::NotFound => Map::new(),
+            Err(why) => return Err(why.into()),
+        };
+
+        let ran = migrate(&mut raw);
+        let store = Store { path, data: Mutex::new(serde_json::from_value(Value::Object(raw))?) };
+        if !ran.is_empty() {
+            println!("Applied store migrations: {}", ran.join(", "));
+            store.save(&store.data.lock().unwrap())?;
+        }
+
+        Ok(store)
+    }
+
+    fn save(&self, data: &StoreData) -> Result<(), Error> {
+        let staging = self.path.with_extension("tmp");
+        fs::write(&staging, serde_json::to_string_pretty(data)?)?;
+        fs::rename(&staging, &self.path)?;
+        Ok(())
+    }
+
+    pub fn flush(&self) -> Result<(), Error> {
+        self.save(&self.data.lock().unwrap())
+    }
+
+    pub fn guild(&self, guild_id: GuildId) -> GuildSettings {
+        self.data.lock().unwrap().guilds.get(&guild_id).cloned().unwrap_or_default()
+    }
+
+    pub fn guilds(&self) -> Vec<(GuildId, GuildSettings)> {
+        self.data
+            .lock()
+            .unwrap()
+            .guilds
+            .iter()
+            .map(|(id, settings)| (*id, settings.clone()))
+            .collect()
+    }
+
+    pub fn update_guild(&self, guild_id: GuildId, update: impl FnOnce(&mut GuildSettings)) -> Result<(), Error> {
+        let mut data = self.data.lock().unwrap();
+        update(data.guilds.entry(guild_id).or_default());
+        self.save(&data)
+    }
+
+    pub fn user(&self, user_id: UserId) -> UserSettings {
+        self.data.lock().unwrap().users.get(&user_id).cloned().unwrap_or_default()
+    }
+
+    pub fn users(&self) -> Vec<(UserId, UserSettings)> {
+        self.data
+            .lock()
+            .unwrap()
+            .users
+            .iter()
+            .map(|(id, settings)| (*id, settings.clone()))
+            .collect()
+    }
+
+    pub fn update_user(&self, user_id: UserId, update: impl FnOnce(&mut UserSettings)) -> Result<(), Error> {
+        let mut data = self.data.lock().unwrap();
+        update(data.users.entry(user_id).or_default());
+        self.save(&data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use super::*;
+
+    fn raw(value: Value) -> Map<String, Value> {
+        match value {
+            Value::Object(map) => map,
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn rewrites_daylight_saving_zones_to_their_old_offsets() {
+        let mut data = raw(json!({
+            "users": {
+                "1": { "timezone": "edt" },
+                "2": { "timezone": "UTC+5:30" },
+                "3": { "timezone": null },
+                "4": {},
+            }
+        }));
+
+        assert_eq!(migrate(&mut data), ["0001_fixed_offset_timezones"]);
+        assert_eq!(data["users"]["1"]["timezone"], "UTC-4");
+        assert_eq!(data["users"]["2"]["timezone"], "UTC+5:30");
+        assert_eq!(data["users"]["3"]["timezone"], Value::Null);
+        assert!(Zone::parse("UTC-4").is_some());
+    }
+
+    #[test]
+    fn migrations_run_once() {
+        let mut data = raw(json!({ "users": { "1": { "timezone": "PST" } } }));
+        migrate(&mut data);
+        data["users"]["1"]["timezone"] = Value::from("PST");
+
+        assert!(migrate(&mut data).is_empty());
+        assert_eq!(data["users"]["1"]["timezone"], "PST");
+        assert_eq!(data["schema_migrations"], json!(["0001_fixed_offset_timezones"]));
+    }
+}
@@ -0,0 +1,120 @@
+use chrono::{DateTime, FixedOffset, Timelike, Utc};
+
+const ZONE_ABBREVIATIONS: [(&str, i32); 4] = [
+    ("UTC", 0),
+    ("GMT", 0),
+    ("IST", 5 * 3600 + 1800),
+    ("JST", 9 * 3600),
+];
+
+const DAYLIGHT_SAVING_ABBREVIATIONS: [&str; 18] = [
+    "EST", "EDT", "CST", "CDT", "MST", "MDT", "PST", "PDT", "AKST", "AKDT",
+    "WET", "WEST", "BST", "CET", "CEST", "EET", "AEST", "AEDT",
+];
+
+pub fn is_daylight_saving(value: &str) -> bool {
+    let value = value.trim().to_ascii_uppercase();
+    DAYLIGHT_SAVING_ABBREVIATIONS.contains(&value.as_str())
+}
+
+/// A fixed UTC offset. Zones that observe daylight saving aren't supported,
+/// so their abbreviations are rejected instead of being pinned to one offset.
+#[derive(Debug, Clone)]
+pub struct Zone {
+    pub name: String,
+    pub offset: FixedOffset,
+}
+
+impl Zone {
+    pub fn parse(value: &str) -> Option<Zone> {
+        let value = value.trim().to_ascii_uppercase();
+        if let Some((name, seconds)) = ZONE_ABBREVIATIONS.iter().find(|(name, _)| *name == value) {
+            return Some(Zone { name: name.to_string(), offset: FixedOffset::east_opt(*seconds)? });
+        }
+
+        let offset = value.strip_prefix("UTC").or_else(|| value.strip_prefix("GMT")).unwrap_or(&value);
+        let (sign, rest) = match offset.chars().next()? {
+            '+' => (1, &offset[1..]),
+            '-' => (-1, &offset[1..]),
+            _ => return None,
+        };
+        if !rest.is_ascii() {
+            return None;
+        }
+        let (hours, minutes) = match rest.split_once(':') {
+            Some((hours, minutes)) => (hours, minutes),
+            None if rest.len() > 2 => rest.split_at(rest.len() - 2),
+            None => (rest, "0"),
+        };
+        let hours: i32 = hours.parse().ok().filter(|hours| *hours <= 14)?;
+        let minutes: i32 = minutes.parse().ok().filter(|minutes| *minutes < 60)?;
+        let offset = FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60))?;
+
+        Some(Zone { name: format!("UTC{}", offset), offset })
+    }
+}
+
+pub fn parse_clock(value: &str) -> Option<u32> {
+    let (hours, minutes) = value.trim().split_once(':')?;
+    let hours: u32 = hours.parse().ok().filter(|hours| *hours < 24)?;
+    let minutes: u32 = minutes.parse().ok().filter(|minutes| *minutes < 60)?;
+    Some(hours * 60 + minutes)
+}
+
+pub fn format_clock(minute: u32) -> String {
+    format!("{:02}:{:02}", minute / 60, minute % 60)
+}
+
+pub fn local_minute(time: DateTime<Utc>, zone: Option<&Zone>) -> u32 {
+    let local = match zone {
+        Some(zone) => time.with_timezone(&zone.offset).time(),
+        None => time.time(),
+    };
+    local.hour() * 60 + local.minute()
+}
+
+pub fn format_dual(time: DateTime<Utc>, zone: Option<&Zone>) -> String {
+    let utc = time.format("%Y-%m-%d %H:%M UTC").to_string();
+    let Some(zone) = zone else {
+        return utc;
+    };
+
+    let local = time.with_timezone(&zone.offset);
+    let local = if local.date_naive() == time.date_naive() {
+        local.format("%H:%M")
+    } else {
+        local.format("%b %d %H:%M")
+    };
+
+    format!("{} ({} {})", utc, local, zone.name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_fixed_zones_and_offsets() {
+        assert_eq!(Zone::parse("utc").unwrap().offset.local_minus_utc(), 0);
+        assert_eq!(Zone::parse("JST").unwrap().offset.local_minus_utc(), 9 * 3600);
+        assert_eq!(Zone::parse("UTC-4").unwrap().offset.local_minus_utc(), -4 * 3600);
+        assert_eq!(Zone::parse("UTC+5:30").unwrap().offset.local_minus_utc(), 5 * 3600 + 1800);
+        assert_eq!(Zone::parse("+0930").unwrap().offset.local_minus_utc(), 9 * 3600 + 1800);
+    }
+
+    #[test]
+    fn rejects_daylight_saving_abbreviations() {
+        for zone in ["EDT", "pst", "CET", "BST", "AEST"] {
+            assert!(Zone::parse(zone).is_none(), "{} should be rejected", zone);
+            assert!(is_daylight_saving(zone));
+        }
+        assert!(!is_daylight_saving("UTC+2"));
+    }
+
+    #[test]
+    fn rejects_out_of_range_offsets() {
+        assert!(Zone::parse("UTC+15").is_none());
+        assert!(Zone::parse("UTC+2:60").is_none());
+        assert!(Zone::parse("Europe/London").is_none());
+    }
+}